@@ -0,0 +1,68 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=AOC_INPUT_DIR");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    example_tests(&manifest_dir, &out_dir);
+
+    if env::var_os("CARGO_FEATURE_EMBEDDED").is_some() {
+        embedded_inputs(&manifest_dir, &out_dir);
+    }
+}
+
+// Generate a test for each example in examples/, included by tests/examples.rs.
+// Each runs the example through the solver and checks the answers beside it.
+fn example_tests(manifest_dir: &Path, out_dir: &Path) {
+    let examples_dir = manifest_dir.join("examples");
+    println!("cargo:rerun-if-changed={}", examples_dir.display());
+
+    let mut names: Vec<String> = match fs::read_dir(&examples_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+            .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+
+    let mut tests = String::new();
+    for name in names {
+        // Test names must be identifiers.
+        let test_name: String = name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        tests.push_str(&format!("#[test]\nfn {test_name}() {{\n    check_example({name:?});\n}}\n\n"));
+    }
+
+    fs::write(out_dir.join("example_tests.rs"), tests).unwrap();
+}
+
+// With the "embedded" feature, generate a table of the puzzle inputs found in the
+// input directory so they get compiled into the crate.  The directory is
+// $AOC_INPUT_DIR, or "inputs" in the crate root.  Missing days are left as None.
+fn embedded_inputs(manifest_dir: &Path, out_dir: &Path) {
+    let input_dir = match env::var_os("AOC_INPUT_DIR") {
+        Some(dir) => manifest_dir.join(dir),
+        None => manifest_dir.join("inputs"),
+    };
+    println!("cargo:rerun-if-changed={}", input_dir.display());
+
+    let mut table = String::from("pub static EMBEDDED_INPUTS: [Option<&str>; 25] = [\n");
+    for day in 1..=25 {
+        let path = input_dir.join(format!("day{day:02}.txt"));
+        println!("cargo:rerun-if-changed={}", path.display());
+        match path.canonicalize() {
+            Ok(path) => table.push_str(&format!("    Some(include_str!({:?})),\n", path)),
+            Err(_) => table.push_str("    None,\n"),
+        }
+    }
+    table.push_str("];\n");
+
+    fs::write(out_dir.join("embedded_inputs.rs"), table).unwrap();
+}
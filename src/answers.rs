@@ -0,0 +1,317 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::day::Answer;
+
+// Known correct answers are kept in a small subset of TOML, one table per day:
+//
+//   [day17]
+//   part1 = "4,3,2,6,4,5,3,2,4"
+//   part2 = 164540892147389
+//
+// Integer answers are integers, tuples are arrays of integers, e.g. [24, 48], and
+// other answers are strings.  Integers too big for TOML are written as strings.
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+const HEADER: &str = "\
+# Known correct answers for each day's puzzle input, checked by the runner
+# and by the tests.  Running with --record adds answers that aren't here yet.
+";
+
+// How a computed answer compares with the known answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Wrong,
+    Unknown,  // There's no known answer.
+}
+
+impl Check {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Check::Correct => "✓",
+            Check::Wrong => "✗",
+            Check::Unknown => "?",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::Correct => "correct",
+            Check::Wrong => "wrong",
+            Check::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnswerKey {
+    answers: BTreeMap<(usize, usize), Answer>,  // Keyed by (day, part)
+}
+
+impl AnswerKey {
+    // Read answers from text.  Errors say which line is wrong.
+    pub fn parse(text: &str) -> Result<AnswerKey, String> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let err = |msg: &str| format!("line {}: {msg} ({line:?})", idx+1);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[') {
+                let n = table.strip_suffix(']')
+                    .and_then(|t| t.strip_prefix("day"))
+                    .and_then(|n| n.parse::<usize>().ok());
+                match n {
+                    Some(n) if (1..=25).contains(&n) => { day = Some(n); }
+                    _ => { return Err(err("expected a day like [day17]")); }
+                }
+            }
+            else if let Some((key, value)) = line.split_once('=') {
+                let part = match key.trim() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => { return Err(err("expected part1 or part2")); }
+                };
+                let Some(day) = day else {
+                    return Err(err("answer comes before any [dayN]"));
+                };
+                let Some(answer) = parse_value(value.trim()) else {
+                    return Err(err("expected an integer, an array of integers or a quoted string"));
+                };
+                answers.insert((day, part), answer);
+            }
+            else {
+                return Err(err("expected [dayN] or partN = answer"));
+            }
+        }
+
+        Ok(AnswerKey { answers })
+    }
+
+    // Read answers from a file.  A missing file has no answers.
+    pub fn load(path: &Path) -> Result<AnswerKey, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => AnswerKey::parse(&text).map_err(|e| format!("{}, {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(AnswerKey::default()),
+            Err(e) => Err(format!("couldn't read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from(HEADER);
+        let mut last_day = None;
+
+        for ((day, part), answer) in &self.answers {
+            if last_day != Some(*day) {
+                out.push_str(&format!("\n[day{day}]\n"));
+                last_day = Some(*day);
+            }
+            match answer {
+                Answer::Numeric(n) => out.push_str(&format!("part{part} = {n}\n")),
+                Answer::Signed(n) => out.push_str(&format!("part{part} = {n}\n")),
+                Answer::Tuple(values) => {
+                    let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                    out.push_str(&format!("part{part} = [{}]\n", values.join(", ")));
+                }
+                Answer::Big(_) | Answer::Grid(_) | Answer::String(_) => {
+                    out.push_str(&format!("part{part} = {}\n", quote(&answer.to_string())));
+                }
+                Answer::None => (),
+            }
+        }
+
+        out
+    }
+
+    // Number of known answers.
+    pub fn count(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    // Record an answer, unless one is already known.  Returns true if it was added.
+    pub fn add(&mut self, day: usize, part: usize, answer: &Answer) -> bool {
+        if *answer == Answer::None || self.answers.contains_key(&(day, part)) {
+            return false;
+        }
+
+        self.answers.insert((day, part), answer.clone());
+
+        true
+    }
+
+    pub fn check(&self, day: usize, part: usize, answer: Option<&Answer>) -> Check {
+        match self.get(day, part) {
+            None => Check::Unknown,
+            Some(known) if Some(known) == answer => Check::Correct,
+            Some(_) => Check::Wrong,
+        }
+    }
+}
+
+// Read an answer written as a TOML value, with an optional comment after it.
+pub(crate) fn parse_value(value: &str) -> Option<Answer> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let mut s = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    // Only a comment may follow the closing quote.
+                    let rest = chars.as_str().trim();
+                    return (rest.is_empty() || rest.starts_with('#')).then_some(Answer::String(s));
+                }
+                '\\' => {
+                    match chars.next()? {
+                        '"' => s.push('"'),
+                        '\\' => s.push('\\'),
+                        'n' => s.push('\n'),
+                        't' => s.push('\t'),
+                        _ => { return None; }
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+
+        None
+    }
+    else {
+        let value = match value.split_once('#') {
+            Some((v, _comment)) => v.trim(),
+            None => value,
+        };
+        let value = value.replace('_', "");
+        if let Some(values) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            values.split(',')
+                .map(|v| v.trim().parse::<isize>().ok())
+                .collect::<Option<Vec<isize>>>()
+                .map(Answer::Tuple)
+        }
+        else if let Ok(n) = value.parse::<usize>() {
+            Some(Answer::Numeric(n))
+        }
+        else {
+            value.parse::<isize>().ok().map(Answer::Signed)
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::answers::{ANSWERS_FILE, AnswerKey, Check};
+    use crate::day::Answer;
+
+    const EXAMPLE: &str = "\
+# Answers
+[day1]
+part1 = 11
+part2 = 31  # from the example
+
+[day17]
+part1 = \"4,6,3,5,6,3,5,2,1,0\"
+";
+
+    #[test]
+    fn test_parse() {
+        let key = AnswerKey::parse(EXAMPLE).unwrap();
+
+        assert_eq!(key.get(1, 1), Some(&Answer::Numeric(11)));
+        assert_eq!(key.get(1, 2), Some(&Answer::Numeric(31)));
+        assert_eq!(key.get(17, 1), Some(&Answer::String(String::from("4,6,3,5,6,3,5,2,1,0"))));
+        assert_eq!(key.get(17, 2), None);
+
+        assert_eq!(AnswerKey::parse("part1 = 5\n"), Err(String::from("line 1: answer comes before any [dayN] (\"part1 = 5\")")));
+        assert!(AnswerKey::parse("[day26]\n").is_err());
+        assert!(AnswerKey::parse("[day1]\npart3 = 5\n").is_err());
+        assert!(AnswerKey::parse("[day1]\npart1 = \"5\n").is_err());
+        assert!(AnswerKey::parse("[day1]\npart1 = five\n").is_err());
+        assert!(AnswerKey::parse("[day1]\npart1 = [1, x]\n").is_err());
+
+        let key = AnswerKey::parse("[day18]\npart1 = -22\npart2 = [6, 1]  # x,y\n").unwrap();
+        assert_eq!(key.get(18, 1), Some(&Answer::Signed(-22)));
+        assert_eq!(key.get(18, 2), Some(&Answer::String(String::from("6,1"))));
+    }
+
+    #[test]
+    fn test_check() {
+        let key = AnswerKey::parse(EXAMPLE).unwrap();
+
+        assert_eq!(key.check(1, 1, Some(&Answer::Numeric(11))), Check::Correct);
+        assert_eq!(key.check(1, 1, Some(&Answer::Numeric(12))), Check::Wrong);
+        assert_eq!(key.check(1, 1, None), Check::Wrong);
+        assert_eq!(key.check(2, 1, Some(&Answer::Numeric(12))), Check::Unknown);
+    }
+
+    #[test]
+    fn test_add_and_save() {
+        let mut key = AnswerKey::parse(EXAMPLE).unwrap();
+
+        // Known answers aren't replaced.
+        assert!(!key.add(1, 1, &Answer::Numeric(12)));
+        assert!(!key.add(2, 1, &Answer::None));
+        assert!(key.add(17, 2, &Answer::Numeric(117440)));
+        assert!(key.add(18, 1, &Answer::Signed(-22)));
+        assert!(key.add(18, 2, &Answer::coord(6, 1)));
+        assert!(key.add(19, 1, &"123456789012345678901234567890".parse().unwrap()));
+        assert!(key.add(19, 2, &Answer::Grid(vec![String::from("#."), String::from(".#")])));
+        assert_eq!(key.get(1, 1), Some(&Answer::Numeric(11)));
+
+        let text = key.to_toml();
+        assert!(text.ends_with("\
+[day17]
+part1 = \"4,6,3,5,6,3,5,2,1,0\"
+part2 = 117440
+
+[day18]
+part1 = -22
+part2 = [6, 1]
+
+[day19]
+part1 = \"123456789012345678901234567890\"
+part2 = \"#.\\n.#\"
+"));
+        assert_eq!(AnswerKey::parse(&text), Ok(key));
+    }
+
+    #[test]
+    fn test_answers_file() {
+        // The answers kept with the code are readable, and rewriting them changes nothing.
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE);
+        let key = AnswerKey::load(&path).unwrap();
+
+        assert_eq!(key.get(17, 1), Some(&Answer::String(String::from("4,3,2,6,4,5,3,2,4"))));
+        assert_eq!(key.to_toml(), std::fs::read_to_string(&path).unwrap());
+    }
+}
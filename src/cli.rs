@@ -0,0 +1,398 @@
+// Command line handling for the aoc2024 binary.
+
+use crate::params::parse_override;
+use crate::report::Format;
+use crate::viz;
+
+// Runs of each part when benchmarking, unless --repeat says otherwise.
+pub const DEFAULT_BENCH_RUNS: usize = 10;
+
+pub const USAGE: &str = "\
+Usage: aoc2024 [options]
+       aoc2024 watch --day <n> [options]
+       aoc2024 debug17 <file>
+
+Watch mode runs one day, then runs it again whenever its input changes, and
+rebuilds and restarts when its source changes.
+
+debug17 steps through a day 17 program in the file, taking commands from stdin.
+Type help there to see them.
+
+Options:
+  -d, --day <days>      Days to run, e.g. 5, 1-5 or 1-5,17 (default: all)
+  -p, --part <1|2>      Run only one part (default: both)
+  -i, --input <file|->  Read the input from a file, or stdin for '-'.
+                        (Only allowed when running a single day.)
+      --inputs <dir>    Directory holding dayNN.txt input files
+                        (default: $AOC_INPUT_DIR, or inputs)
+  -r, --repeat <n>      Run each part n times (default: 1, or 10 with --bench)
+  -b, --bench           Report timing statistics for each part instead of answers
+      --answers <file>  File of known answers to check against (default: answers.toml)
+      --record          Add answers that aren't known yet to the answers file
+  -j, --jobs <n>        Run up to n parts at once, on separate threads (default: 1)
+  -f, --format <fmt>    Output format: table, json or csv (default: table)
+      --param <name=value>
+                        Set a puzzle parameter, e.g. --param size=7 for the examples
+                        of days that take one (see --list).  May be repeated.
+      --viz[=<fmt>]     Draw the days that can, instead of solving them: in the
+                        terminal with ansi (the default), or as ppm or png images
+      --viz-dir <dir>   Directory the images are saved in (default: viz)
+  -l, --list            List the solvers and exit
+  -h, --help            Show this message
+";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub days: Vec<usize>,           // Days to run, in the order they'll be reported.
+    pub part: Option<usize>,        // Run just this part, or both if None.
+    pub input: Option<String>,      // Input path overriding the day's input, "-" is stdin.
+    pub input_dir: Option<String>,  // Directory of dayNN.txt inputs.
+    pub repeat: usize,              // Number of times to run each part.
+    pub bench: bool,                // Report timing statistics rather than answers.
+    pub format: Format,             // How to write the results.
+    pub jobs: usize,                // Number of parts to run at once.
+    pub answers: Option<String>,    // Path of the known answers file.
+    pub record: bool,               // Save new answers to the answers file.
+    pub params: Vec<(String, String)>,  // Puzzle parameters replacing the days' defaults, as (name, value).
+    pub watch: bool,                // Keep running a day as its input and source change.
+    pub debug17: Option<String>,    // Debug the day 17 program in this file.
+    pub viz: Option<viz::Format>,   // Draw the days rather than solving them.
+    pub viz_dir: Option<String>,    // Directory for images drawn by --viz.
+    pub list: bool,                 // List the solvers and exit.
+    pub help: bool,                 // Show usage and exit.
+}
+
+impl Options {
+    // Parse command line arguments (not including the program name.)
+    pub fn parse<I>(args: I) -> Result<Options, String>
+        where I: IntoIterator<Item = String>
+    {
+        let mut days = None;
+        let mut part = None;
+        let mut input = None;
+        let mut input_dir = None;
+        let mut repeat = None;
+        let mut bench = false;
+        let mut format = Format::Table;
+        let mut jobs = 1;
+        let mut answers = None;
+        let mut record = false;
+        let mut params = Vec::new();
+        let mut watch = false;
+        let mut debug17 = None;
+        let mut viz = None;
+        let mut viz_dir = None;
+        let mut list = false;
+        let mut help = false;
+
+        let mut args = args.into_iter().peekable();
+        if args.peek().is_some_and(|arg| arg == "watch") {
+            args.next();
+            watch = true;
+        }
+        else if args.peek().is_some_and(|arg| arg == "debug17") {
+            args.next();
+            match args.next() {
+                Some(path) => { debug17 = Some(path); }
+                None => { return Err(String::from("debug17 needs the file holding the program")); }
+            }
+        }
+
+        while let Some(arg) = args.next() {
+            // Accept both "--day 5" and "--day=5"
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+
+            let mut value = |name: &str| -> Result<String, String> {
+                match inline_value.clone().or_else(|| args.next()) {
+                    Some(v) => Ok(v),
+                    None => Err(format!("Missing value for {name}")),
+                }
+            };
+
+            match flag.as_str() {
+                "-d" | "--day" => {
+                    days = Some(parse_days(&value(&flag)?)?);
+                }
+                "-p" | "--part" => {
+                    let v = value(&flag)?;
+                    match v.as_str() {
+                        "1" => { part = Some(1); }
+                        "2" => { part = Some(2); }
+                        _ => { return Err(format!("Part must be 1 or 2, not '{v}'")); }
+                    }
+                }
+                "-i" | "--input" => {
+                    input = Some(value(&flag)?);
+                }
+                "--inputs" => {
+                    input_dir = Some(value(&flag)?);
+                }
+                "-r" | "--repeat" => {
+                    let v = value(&flag)?;
+                    repeat = match v.parse::<usize>() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => { return Err(format!("Repeat count must be a positive integer, not '{v}'")); }
+                    };
+                }
+                "-b" | "--bench" => {
+                    bench = true;
+                }
+                "-j" | "--jobs" => {
+                    let v = value(&flag)?;
+                    jobs = match v.parse::<usize>() {
+                        Ok(n) if n > 0 => n,
+                        _ => { return Err(format!("Job count must be a positive integer, not '{v}'")); }
+                    };
+                }
+                "--answers" => {
+                    answers = Some(value(&flag)?);
+                }
+                "--record" => {
+                    record = true;
+                }
+                "-f" | "--format" => {
+                    let v = value(&flag)?;
+                    format = match Format::parse(&v) {
+                        Some(f) => f,
+                        None => { return Err(format!("Format must be table, json or csv, not '{v}'")); }
+                    };
+                }
+                "--param" => {
+                    params.push(parse_override(&value(&flag)?)?);
+                }
+                "--viz" => {
+                    // The format is optional, so only "--viz=png" gives one.
+                    viz = match inline_value.as_deref() {
+                        None => Some(viz::Format::Ansi),
+                        Some(v) => match viz::Format::parse(v) {
+                            Some(f) => Some(f),
+                            None => { return Err(format!("Visualization format must be ansi, ppm or png, not '{v}'")); }
+                        }
+                    };
+                }
+                "--viz-dir" => {
+                    viz_dir = Some(value(&flag)?);
+                }
+                "-l" | "--list" => {
+                    list = true;
+                }
+                "-h" | "--help" => {
+                    help = true;
+                }
+                _ => {
+                    return Err(format!("Unrecognized argument '{arg}'"));
+                }
+            }
+        }
+
+        let days = days.unwrap_or_else(|| (1..=25).collect());
+        let repeat = repeat.unwrap_or(if bench { DEFAULT_BENCH_RUNS } else { 1 });
+
+        if input.is_some() && days.len() != 1 {
+            return Err(String::from("--input can only be used when running a single day"));
+        }
+
+        if watch {
+            if days.len() != 1 {
+                return Err(String::from("watch needs a single day, given with --day"));
+            }
+            if input.as_deref() == Some("-") {
+                return Err(String::from("watch can't read the input from stdin"));
+            }
+            if record {
+                return Err(String::from("--record can't be used with watch"));
+            }
+        }
+
+        if viz.is_some() && (watch || bench || record) {
+            return Err(String::from("--viz can't be used with watch, --bench or --record"));
+        }
+
+        // Answers found with other parameters aren't answers to the real puzzle.
+        if record && !params.is_empty() {
+            return Err(String::from("--record can't be used with --param"));
+        }
+
+        Ok(Options { days, part, input, input_dir, repeat, bench, format, jobs, answers, record, params, watch, debug17, viz, viz_dir, list, help })
+    }
+
+    // Should this part be run?
+    pub fn runs_part(&self, part: usize) -> bool {
+        match self.part {
+            Some(p) => p == part,
+            None => true,
+        }
+    }
+}
+
+// Parse a day list like "5", "1-5" or "1-5,17" into a list of days.
+fn parse_days(spec: &str) -> Result<Vec<usize>, String> {
+    let mut days = Vec::new();
+
+    for item in spec.split(',') {
+        let item = item.trim();
+        let (first, last) = match item.split_once('-') {
+            Some((a, b)) => (parse_day(a)?, parse_day(b)?),
+            None => {
+                let day = parse_day(item)?;
+                (day, day)
+            }
+        };
+
+        if first > last {
+            return Err(format!("Day range '{item}' is backwards"));
+        }
+
+        for day in first..=last {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+    }
+
+    Ok(days)
+}
+
+fn parse_day(s: &str) -> Result<usize, String> {
+    match s.trim().parse::<usize>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Day '{s}' is invalid, days are 1 to 25")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{Options, DEFAULT_BENCH_RUNS, parse_days};
+    use crate::report::Format;
+    use crate::viz;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options.days, (1..=25).collect::<Vec<usize>>());
+        assert_eq!(options.part, None);
+        assert_eq!(options.input, None);
+        assert_eq!(options.input_dir, None);
+        assert_eq!(options.repeat, 1);
+        assert!(!options.bench);
+        assert_eq!(options.format, Format::Table);
+        assert_eq!(options.jobs, 1);
+        assert_eq!(options.answers, None);
+        assert!(!options.record);
+        assert!(options.params.is_empty());
+        assert!(!options.watch);
+        assert_eq!(options.debug17, None);
+        assert_eq!(options.viz, None);
+        assert_eq!(options.viz_dir, None);
+        assert!(options.runs_part(1));
+        assert!(options.runs_part(2));
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("5"), Ok(vec![5]));
+        assert_eq!(parse_days("1-5,17"), Ok(vec![1, 2, 3, 4, 5, 17]));
+        assert_eq!(parse_days("3,1-3"), Ok(vec![3, 1, 2]));
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("5-3").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn test_options() {
+        let options = parse(&["--day", "17", "-p", "2", "--input=-", "--repeat", "3"]).unwrap();
+
+        assert_eq!(options.days, vec![17]);
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input, Some(String::from("-")));
+        assert_eq!(options.repeat, 3);
+        assert!(!options.runs_part(1));
+        assert!(options.runs_part(2));
+
+        let options = parse(&["--inputs", "data"]).unwrap();
+        assert_eq!(options.input_dir, Some(String::from("data")));
+
+        // Benchmarks repeat each part, by default.
+        let options = parse(&["--bench"]).unwrap();
+        assert!(options.bench);
+        assert_eq!(options.repeat, DEFAULT_BENCH_RUNS);
+
+        let options = parse(&["-b", "-r", "3"]).unwrap();
+        assert_eq!(options.repeat, 3);
+
+        let options = parse(&["--format=json", "-j", "8"]).unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.jobs, 8);
+
+        let options = parse(&["--answers", "mine.toml", "--record"]).unwrap();
+        assert_eq!(options.answers, Some(String::from("mine.toml")));
+        assert!(options.record);
+
+        let options = parse(&["--day", "18", "--param", "size=7", "--param=t=12"]).unwrap();
+        assert_eq!(options.params, vec![
+            (String::from("size"), String::from("7")),
+            (String::from("t"), String::from("12")),
+        ]);
+    }
+
+    #[test]
+    fn test_watch() {
+        let options = parse(&["watch", "--day", "18", "--param", "size=7"]).unwrap();
+
+        assert!(options.watch);
+        assert_eq!(options.days, vec![18]);
+    }
+
+    #[test]
+    fn test_debug17() {
+        let options = parse(&["debug17", "program.txt"]).unwrap();
+        assert_eq!(options.debug17, Some(String::from("program.txt")));
+
+        assert!(parse(&["debug17"]).is_err());
+    }
+
+    #[test]
+    fn test_viz() {
+        let options = parse(&["--day", "6", "--viz"]).unwrap();
+        assert_eq!(options.viz, Some(viz::Format::Ansi));
+        assert_eq!(options.days, vec![6]);
+
+        let options = parse(&["--viz=png", "--viz-dir", "pictures"]).unwrap();
+        assert_eq!(options.viz, Some(viz::Format::Png));
+        assert_eq!(options.viz_dir, Some(String::from("pictures")));
+
+        assert!(parse(&["--viz=gif"]).is_err());
+        assert!(parse(&["--viz", "--bench"]).is_err());
+        assert!(parse(&["watch", "--day", "6", "--viz"]).is_err());
+    }
+
+    #[test]
+    fn test_bad_options() {
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["--repeat", "0"]).is_err());
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["--day"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--param", "size"]).is_err());
+        assert!(parse(&["--param", "size=7", "--record"]).is_err());
+
+        // Watch mode needs one day, and an input it can watch.
+        assert!(parse(&["watch"]).is_err());
+        assert!(parse(&["watch", "--day", "1-2"]).is_err());
+        assert!(parse(&["watch", "--day", "1", "--input", "-"]).is_err());
+        assert!(parse(&["--day", "1", "watch"]).is_err());
+
+        // An input file only makes sense for one day.
+        assert!(parse(&["--day", "1-2", "--input", "x.txt"]).is_err());
+    }
+}
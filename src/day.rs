@@ -1,18 +1,182 @@
-#[allow(dead_code)]
-#[derive(PartialEq, Eq, Debug)]
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use num::BigInt;
+
+use crate::error::SolveError;
+use crate::params::{Param, Params};
+use crate::viz::Visualize;
+
+// An answer to one part of a puzzle.
+//
+// Answers compare equal when they represent the same thing, so Numeric(5),
+// Signed(5) and Big(5) are all equal, and any answer equals a String of its
+// text, e.g. Tuple(vec![24, 48]) == String("24,48").  That lets answers read
+// from text (see FromStr) be checked against the answers the solvers give.
+#[derive(Debug, Clone)]
 pub enum Answer {
     None,
     Numeric(usize),
+    Signed(isize),
+    Big(BigInt),
+    Tuple(Vec<isize>),   // Coordinates and the like, written as "24,48"
+    Grid(Vec<String>),   // Multi-line ASCII art, one String per row
     String(String),
 }
 
+impl Answer {
+    pub fn coord(x: usize, y: usize) -> Answer {
+        Answer::Tuple(vec![x as isize, y as isize])
+    }
+
+    // The value of an integer answer.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Answer::Numeric(n) => Some(BigInt::from(*n)),
+            Answer::Signed(n) => Some(BigInt::from(*n)),
+            Answer::Big(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        match (self, other) {
+            (Answer::None, Answer::None) => true,
+            (Answer::None, _) | (_, Answer::None) => false,
+            _ => match (self.to_bigint(), other.to_bigint()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.to_string() == other.to_string(),
+            }
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::None => Ok(()),
+            Answer::Numeric(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Big(n) => write!(f, "{n}"),
+            Answer::Tuple(values) => {
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write!(f, "{v}")?;
+                }
+                Ok(())
+            }
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::String(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+// Read an answer from text, choosing the narrowest representation:
+// "" is None, integers are Numeric, Signed or Big, comma separated integers are a
+// Tuple, several lines are a Grid and anything else is a String.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Answer, Infallible> {
+        let text = s.trim_end_matches('\n');
+
+        if text.contains('\n') {
+            return Ok(Answer::Grid(text.lines().map(|l| l.to_string()).collect()));
+        }
+
+        let text = text.trim();
+        if text.is_empty() {
+            Ok(Answer::None)
+        }
+        else if let Ok(n) = text.parse::<usize>() {
+            Ok(Answer::Numeric(n))
+        }
+        else if let Ok(n) = text.parse::<isize>() {
+            Ok(Answer::Signed(n))
+        }
+        else if let Ok(n) = text.parse::<BigInt>() {
+            Ok(Answer::Big(n))
+        }
+        else if let Ok(values) = text.split(',').map(|v| v.trim().parse::<isize>()).collect::<Result<Vec<isize>, _>>() {
+            Ok(Answer::Tuple(values))
+        }
+        else {
+            Ok(Answer::String(text.to_string()))
+        }
+    }
+}
+
 pub trait Day: Sync {
 
-    fn part1(&self, _input: &str) -> Answer {
-        Answer::None
+    // The parameters the parts take, with their defaults.  See params.rs.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    fn part1(&self, _input: &str, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(Answer::None)
+    }
+
+    fn part2(&self, _input: &str, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(Answer::None)
+    }
+
+    // The day's pictures, for days that can draw themselves.  See viz.rs.
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num::BigInt;
+
+    use crate::day::Answer;
+
+    fn parse(s: &str) -> Answer {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(parse(""), Answer::None));
+        assert!(matches!(parse("42"), Answer::Numeric(42)));
+        assert!(matches!(parse("-42"), Answer::Signed(-42)));
+        assert!(matches!(parse("123456789012345678901234567890"), Answer::Big(_)));
+        assert!(matches!(parse("24,48"), Answer::Tuple(ref v) if *v == vec![24, 48]));
+        assert!(matches!(parse("#..\n.#.\n"), Answer::Grid(ref rows) if rows.len() == 2));
+        assert!(matches!(parse("az,ed,hz"), Answer::String(_)));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::Signed(-7).to_string(), "-7");
+        assert_eq!(Answer::coord(6, 1).to_string(), "6,1");
+        assert_eq!(Answer::Grid(vec![String::from("#."), String::from(".#")]).to_string(), "#.\n.#");
+
+        // Text survives a round trip.
+        for text in ["", "5", "-5", "99999999999999999999999", "1,-2,3", "#.\n.#", "co,de,ka,ta"] {
+            assert_eq!(parse(text).to_string(), text);
+        }
     }
 
-    fn part2(&self, _input: &str) -> Answer {
-        Answer::None
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::Numeric(5), Answer::Signed(5));
+        assert_eq!(Answer::Big(BigInt::from(5)), Answer::Numeric(5));
+        assert_ne!(Answer::Signed(-5), Answer::Numeric(5));
+
+        // Anything matches its text.
+        assert_eq!(Answer::coord(24, 48), Answer::String(String::from("24,48")));
+        assert_eq!(Answer::Numeric(12), Answer::String(String::from("12")));
+        assert_eq!(Answer::Grid(vec![String::from("#."), String::from(".#")]), Answer::String(String::from("#.\n.#")));
+        assert_ne!(Answer::coord(24, 48), Answer::String(String::from("48,24")));
+
+        // None is only equal to None.
+        assert_eq!(Answer::None, Answer::None);
+        assert_ne!(Answer::None, Answer::String(String::new()));
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,13 @@
 use std::iter::zip;
-use lazy_static::lazy_static;
-use regex::Regex;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref LINE_RE: Regex = Regex::new("(\\d+)\\s+(\\d+)").unwrap();
-}
+const DAY: usize = 1;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -20,33 +18,26 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input 
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut left: Vec<isize> = Vec::new();
         let mut right: Vec<isize> = Vec::new();
 
-        for line in text.lines() {
-            match LINE_RE.captures(line) {                    
-                Some(captures) => {
-                    let a = captures[1].parse().unwrap();
-                    let b= captures[2].parse().unwrap();                    
-                    left.push(a);
-                    right.push(b);
-                }
-                None => {
-                    // Ignore this line.
-                    println!("Ignored input: {line}");
-                }
-            }
+        for line in parse::lines(DAY, text) {
+            let [a, b] = line.ints_n("expected two integers")?;
+            left.push(a);
+            right.push(b);
         }
 
         left.sort();
         right.sort();
 
-        Input { left, right }
+        Ok(Input { left, right })
     }
 }
 
+solver!(Day1, year: 2024, day: 1, title: "Historian Hysteria", tags: ["lists", "sorting"]);
+
 pub struct Day1 {
 }
 
@@ -60,23 +51,23 @@ impl Day1 {
 impl Day for Day1 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let dist_sum: isize = zip(&input.left, &input.right)
             .map(|pair| { (pair.0-pair.1).abs() }) 
             .sum();
 
-        Answer::Numeric(dist_sum as usize)
+        Ok(Answer::Numeric(dist_sum as usize))
     }
 
-    fn part2(&self, text: &str) -> Answer {
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
 
         // Read input file into Input struct, then sum the results.
    
         // (The diff between part1 and part2 is the flag passed to read_input.  It
         // interprets numbers embedded in lines differently for each part.)
-        let input = Input::read(text);
+        let input = timing::parse(|| Input::read(text))?;
 
         let similarity = input.left.iter().map(|l| {
             let match_count = input.right.iter().filter(|r| {l == *r}).count();
@@ -84,7 +75,7 @@ impl Day for Day1 {
             *l as usize * match_count
         }).sum();
 
-        Answer::Numeric(similarity)
+        Ok(Answer::Numeric(similarity))
     }
 }
 
@@ -93,6 +84,8 @@ mod test {
 
     use crate::day1::{Day1, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::error::ParseError;
     
     const EXAMPLE1: &str = "\
 3   4
@@ -106,7 +99,7 @@ mod test {
     #[test]
     // Read part 1 example and confirm inputs
     fn test_read_part1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
                 
         assert_eq!(input.left.len(), 6);
         assert_eq!(input.right.len(), 6);
@@ -118,21 +111,28 @@ mod test {
         assert_eq!((input.left[5], input.right[5]), (4, 9));
     }
 
+    #[test]
+    // Lines that aren't two integers are reported.
+    fn test_read_error() {
+        let result = Input::read("3   4\n4   x\n");
+
+        assert_eq!(result.err(), Some(ParseError::new(1, 2, 1, "4   x", "expected two integers")));
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d: Day1 = Day1::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(11));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(11)));
     }
 
-
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
         // Based on the example in part 2.
         let d: Day1 = Day1::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(31));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(31)));
     }
     
 }
@@ -1,15 +1,12 @@
 use std::{collections::HashMap, mem};
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::{Param, Params, Value};
+use crate::error::{ParseError, SolveError, parse_field};
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // Use STONE_RE.captures_iter(haystack).  Each cap[1] can be parsed as integer NNNNN
-    static ref STONE_RE: Regex = Regex::new("(\\d+)").unwrap();
-}
+const DAY: usize = 11;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -18,15 +15,16 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let stones = STONE_RE.captures_iter(text)
-            .map(|cap| { 
-                cap[0].parse::<usize>().unwrap() 
-            })
-            .collect();
+        let mut stones = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            for field in line.split_whitespace() {
+                stones.push(parse_field(DAY, idx, line, field)?);
+            }
+        }
 
-        Input { stones }
+        Ok(Input { stones })
     }
 
     fn split_stone(stone: usize) -> Option<(usize, usize)> {
@@ -134,6 +132,8 @@ impl Input {
     }
 }
 
+solver!(Day11, year: 2024, day: 11, title: "Plutonian Pebbles", tags: ["memoization"]);
+
 pub struct Day11 {
 }
 
@@ -146,17 +146,24 @@ impl Day11 {
 
 impl<'a> Day for Day11 {
 
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param { name: "blinks1", default: Value::Unsigned(25), help: "blinks in part 1" },
+            Param { name: "blinks2", default: Value::Unsigned(75), help: "blinks in part 2" },
+        ]
+    }
+
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.blink(25))
+        Ok(Answer::Numeric(input.blink(params.usize("blinks1"))))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.blink2(75))
+        Ok(Answer::Numeric(input.blink2(params.usize("blinks2"))))
     }
 }
 
@@ -166,6 +173,7 @@ mod test {
 
     use crate::day11::{Day11, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -175,7 +183,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.stones.len(), 2);
         assert_eq!(input.stones[0], 125);
@@ -184,7 +192,7 @@ mod test {
 
     #[test]
     fn test_blink() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.blink(1), 3);
         assert_eq!(input.blink(2), 4);
@@ -196,7 +204,7 @@ mod test {
 
     #[test]
     fn test_blink2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.blink2(1), 3);
         assert_eq!(input.blink2(2), 4);
@@ -211,7 +219,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day11::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(55312));
+        assert_eq!(d.part1(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(55312)));
     }
 
     #[test]
@@ -219,7 +227,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day11::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(65601038650482));
+        assert_eq!(d.part2(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(65601038650482)));
     }
     
 }
\ No newline at end of file
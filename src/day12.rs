@@ -1,50 +1,43 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::geom::Dir4;
+use crate::grid::Grid;
+use crate::timing;
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::registry::solver;
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
+const DAY: usize = 12;
+
+// A representation of the puzzle inputs: the garden plots, by plant type.
 struct Input {
-    // puzzle[row][col] -> char
-    map: Vec<Vec<char>>,
+    map: Grid<char>,
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut map: Vec<Vec<char>> = Vec::new();
-
-        for line in text.lines() {
-            let mut row: Vec<char> = Vec::new();
-            for c in line.chars() {
-                row.push(c);
-            }
-            map.push(row);
-        }
+        // The map has to be rectangular, which the grid checks.
+        let map = Grid::parse_chars(DAY, text)?;
 
-        Input { map }
+        Ok(Input { map })
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
-enum Dir {
-    N, E, S, W,
-}
-
 struct Region {
     area: HashSet<(usize, usize)>,
     perimeter: usize,
 
     // set of perimeter crossings ((inner_x, inner_y), (outer_x, outer_y))
-    perimeter_crossings: HashSet<(usize, usize, Dir)>,
+    perimeter_crossings: HashSet<(usize, usize, Dir4)>,
 }
 
 impl Region {
     fn generate(input: &Input, start: (usize, usize)) -> Region {
         // Implement flood fill to find connected area
-        let key = input.map[start.1][start.0];
-        let height = input.map.len();
-        let width = input.map[0].len();
+        let key = input.map[start];
         let mut area = HashSet::new();
         let mut perimeter = 0;
         let mut perimeter_crossings = HashSet::new();
@@ -56,33 +49,22 @@ impl Region {
 
         // Only push things to to_check after they are verified in the area.
         while let Some(cell) = to_check.pop() {
-            for (adj_x, adj_y, dir) in 
-                vec![(cell.0 as isize, cell.1 as isize+1, Dir::N), 
-                     (cell.0 as isize, cell.1 as isize-1, Dir::S), 
-                     (cell.0 as isize+1, cell.1 as isize, Dir::E), 
-                     (cell.0 as isize-1, cell.1 as isize, Dir::W)] {
-                if (adj_x >= 0) & (adj_x < width as isize) & (adj_y >= 0) & (adj_y < height as isize) {
-                    // Adjacent space is in map
-                    let u_adjacent = (adj_x as usize, adj_y as usize);
-                    if input.map[u_adjacent.1][u_adjacent.0] == key {
+            for dir in Dir4::ALL {
+                match input.map.step(cell, dir.delta()) {
+                    Some(adjacent) if input.map[adjacent] == key => {
                         // The adjacent space is part of the same area
-                        if !area.contains(&u_adjacent) {
+                        if !area.contains(&adjacent) {
                             // covered.insert(adjacent);
-                            area.insert(u_adjacent);
-                            to_check.push(u_adjacent);
+                            area.insert(adjacent);
+                            to_check.push(adjacent);
                         }
                     }
-                    else {
-                        // The adjacent space is a different area
+                    _ => {
+                        // The adjacent space is a different area, or off the map.  This is a perimeter
                         perimeter_crossings.insert( (cell.0, cell.1, dir) );
                         perimeter += 1;
                     }
                 }
-                else {
-                    // Adjacent space is off map, this is a perimeter
-                    perimeter_crossings.insert( (cell.0, cell.1, dir) );
-                    perimeter += 1;
-                }
             }
         }
 
@@ -100,8 +82,8 @@ impl Region {
     fn num_sides(&self) -> usize {
         // in_line_sides[Dir][Coord1] -> Vec of Coord2
         // Where Coord1 is X for E/W dir, Y for N/S dir, 
-        let mut in_line_sides: HashMap<Dir, HashMap<usize, Vec<usize>> > = HashMap::new();
-        for d in [Dir::N, Dir::S, Dir::E, Dir::W] {
+        let mut in_line_sides: HashMap<Dir4, HashMap<usize, Vec<usize>> > = HashMap::new();
+        for d in Dir4::ALL {
             in_line_sides.insert(d, HashMap::new());
         }
 
@@ -111,10 +93,10 @@ impl Region {
         // those.  
         for (x, y, dir) in self.perimeter_crossings.iter() {
             let (coord1, coord2) = match dir {
-                Dir::N => (y, x),
-                Dir::S => (y, x),
-                Dir::E => (x, y),
-                Dir::W => (x, y),
+                Dir4::N => (y, x),
+                Dir4::S => (y, x),
+                Dir4::E => (x, y),
+                Dir4::W => (x, y),
             };
             if !in_line_sides[dir].contains_key(coord1) {
                 // Create a vector for this coord1, 
@@ -163,26 +145,38 @@ impl GardenMap {
         let mut regions = Vec::new();
         let mut mapped: HashSet<(usize, usize)> = HashSet::new();
 
-        for y in 0..input.map.len() {
-            for x in 0..input.map[0].len() {
-                if !mapped.contains(&(x, y)) {
-                    // create a new region from area adjacent to (x, y)
-                    let new_region = Region::generate(&input, (x, y));
+        for pos in input.map.positions() {
+            if !mapped.contains(&pos) {
+                // create a new region from area adjacent to pos
+                let new_region = Region::generate(&input, pos);
 
-                    // Mark the whole new region mapped
-                    for cell in new_region.area.iter() {
-                        mapped.insert(*cell);
-                    }
-
-                    regions.push(new_region);
+                // Mark the whole new region mapped
+                for cell in new_region.area.iter() {
+                    mapped.insert(*cell);
                 }
+
+                regions.push(new_region);
             }
         }
 
         GardenMap { regions }
     }
+
+    // The garden with each region in its own colour.
+    fn frame(&self, input: &Input) -> Frame {
+        let mut cells = Grid::new(input.map.width(), input.map.height(), Cell::new(' ', viz::BACKGROUND));
+        for (n, region) in self.regions.iter().enumerate() {
+            for pos in region.area.iter() {
+                cells[*pos] = Cell::new(input.map[*pos], viz::palette(n));
+            }
+        }
+
+        Frame::new(&format!("{} regions", self.regions.len()), cells)
+    }
 }
 
+solver!(Day12, year: 2024, day: 12, title: "Garden Groups", tags: ["grid", "flood-fill"]);
+
 pub struct Day12 {
 }
 
@@ -196,26 +190,39 @@ impl Day12 {
 impl<'a> Day for Day12 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
         let map = GardenMap::new(&input);
 
         let price = map.regions.iter()
             .map(|r| { r.price() })
             .sum::<usize>();
 
-        Answer::Numeric(price)
+        Ok(Answer::Numeric(price))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
         let map = GardenMap::new(&input);
 
         let price = map.regions.iter()
             .map(|r| { r.new_price() })
             .sum::<usize>();
 
-        Answer::Numeric(price)
+        Ok(Answer::Numeric(price))
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+impl Visualize for Day12 {
+    fn frames(&self, text: &str, _params: &Params) -> Result<Vec<Frame>, SolveError> {
+        let input = Input::read(text)?;
+        let map = GardenMap::new(&input);
+
+        Ok(vec![map.frame(&input)])
     }
 }
 
@@ -225,6 +232,8 @@ mod test {
 
     use crate::day12::{Day12, Input, GardenMap};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::viz::Visualize;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -243,18 +252,18 @@ MMMISSJEEE
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.map.len(), 10);
-        assert_eq!(input.map[0].len(), 10);
-        assert_eq!(input.map[0][0], 'R');
-        assert_eq!(input.map[2][0], 'V');
-        assert_eq!(input.map[0][2], 'R');
+        assert_eq!(input.map.height(), 10);
+        assert_eq!(input.map.width(), 10);
+        assert_eq!(input.map[(0, 0)], 'R');
+        assert_eq!(input.map[(0, 2)], 'V');
+        assert_eq!(input.map[(2, 0)], 'R');
     }
 
     #[test]
     fn test_map() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let map = GardenMap::new(&input);
 
         assert_eq!(map.regions.len(), 11);
@@ -272,12 +281,21 @@ MMMISSJEEE
                     .sum::<usize>(), 1206);
     }
 
+    #[test]
+    fn test_frames() {
+        let frames = Day12::new().frames(EXAMPLE1, &Params::default()).unwrap();
+
+        assert_eq!(frames[0].caption, "11 regions");
+        assert_eq!(frames[0].text(), EXAMPLE1);
+        assert_ne!(frames[0].cells[(0, 0)].color, frames[0].cells[(0, 2)].color);
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day12::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(1930));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(1930)));
     }
 
     #[test]
@@ -285,7 +303,7 @@ MMMISSJEEE
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day12::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(1206));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(1206)));
     }
     
 }
\ No newline at end of file
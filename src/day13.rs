@@ -1,16 +1,11 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse::{self, Line};
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref BUTTON_A_RE: Regex = Regex::new("Button A: X([+-]\\d+), Y([+-]\\d+)").unwrap();
-    static ref BUTTON_B_RE: Regex = Regex::new("Button B: X([+-]\\d+), Y([+-]\\d+)").unwrap();
-    static ref PRIZE_RE: Regex = Regex::new("Prize: X=(\\d+), Y=(\\d+)").unwrap();
-}
+const DAY: usize = 13;
 
 struct Game {
     a: (isize, isize),
@@ -61,25 +56,29 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
+        // Each game is two buttons and a prize, with blank lines between games.
         let mut games = Vec::new();
-        let mut a = (0, 0);
-        let mut b = (0, 0);
-        let mut prize;
-        for line in text.lines() {
-            if let Some(cap) = BUTTON_A_RE.captures(line) {
-                a = (cap[1].parse().unwrap(), cap[2].parse().unwrap());
-            }
-            if let Some(cap) = BUTTON_B_RE.captures(line) {
-                b = (cap[1].parse().unwrap(), cap[2].parse().unwrap());
-            }
-            if let Some(cap) = PRIZE_RE.captures(line) {
-                prize = (cap[1].parse().unwrap(), cap[2].parse().unwrap());
-                games.push( Game { a, b, prize });
-            }
+        for section in parse::sections(DAY, text).iter() {
+            let [a, b, prize] = section else {
+                return Err(section[0].error("expected two buttons and a prize"));
+            };
+            let a = Self::read_xy(a, "Button A:", "expected a button like Button A: X+94, Y+34")?;
+            let b = Self::read_xy(b, "Button B:", "expected a button like Button B: X+22, Y+67")?;
+            let prize = Self::read_xy(prize, "Prize:", "expected a prize like Prize: X=8400, Y=5400")?;
+            games.push( Game { a, b, prize });
         }
-        Input { games }
+
+        Ok(Input { games })
+    }
+
+    // Read the X and Y from a line starting with label.
+    fn read_xy(line: &Line, label: &str, msg: &str) -> Result<(isize, isize), ParseError> {
+        line.after(label, msg)?;
+        let [x, y] = line.ints_n(msg)?;
+
+        Ok((x, y))
     }
 
     fn tokens(&self) -> usize {
@@ -115,6 +114,8 @@ impl Input {
     }
 }
 
+solver!(Day13, year: 2024, day: 13, title: "Claw Contraption", tags: ["math", "linear-algebra"]);
+
 pub struct Day13 {
 }
 
@@ -128,16 +129,16 @@ impl Day13 {
 impl<'a> Day for Day13 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.tokens())
+        Ok(Answer::Numeric(input.tokens()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.tokens2())
+        Ok(Answer::Numeric(input.tokens2()))
     }
 }
 
@@ -147,6 +148,7 @@ mod test {
 
     use crate::day13::{Day13, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -170,7 +172,7 @@ Prize: X=18641, Y=10279
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.games.len(), 4);
         assert_eq!(input.games[0].a, (94, 34));
@@ -180,7 +182,7 @@ Prize: X=18641, Y=10279
 
     #[test]
     fn test_soln() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.games[0].soln1(), Some( (80, 40) ));
         assert_eq!(input.games[1].soln1(), None);
@@ -191,7 +193,7 @@ Prize: X=18641, Y=10279
     
     #[test]
     fn test_soln2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.games[0].soln2(), None);
         assert_ne!(input.games[1].soln2(), None);
@@ -201,7 +203,7 @@ Prize: X=18641, Y=10279
     
     #[test]
     fn test_tokens() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.tokens(), 480);
     }
@@ -211,7 +213,7 @@ Prize: X=18641, Y=10279
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day13::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(480));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(480)));
     }
 
     #[test]
@@ -219,7 +221,7 @@ Prize: X=18641, Y=10279
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day13::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(875318608908));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(875318608908)));
     }
     
 }
\ No newline at end of file
@@ -1,28 +1,26 @@
 use std::collections::HashSet;
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::{Param, Params, Value};
+use crate::error::{ParseError, SolveError};
+use crate::geom::{Point, Vector};
+use crate::grid::Grid;
+use crate::parse;
+use crate::timing;
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref LINE_RE: Regex = Regex::new("p=(-?\\d+),(-?\\d+) v=(-?\\d+),(-?\\d+)").unwrap();
-}
+const DAY: usize = 14;
 
 struct Robot {
-    pos: (isize, isize),
-    vel: (isize, isize),
+    pos: Point,
+    vel: Vector,
 }
 
 impl Robot {
-    fn project(&self, field: (isize, isize), time: isize) -> (isize, isize) {
-        let px = (self.pos.0 + self.vel.0*time).rem_euclid(field.0);
-        let py = (self.pos.1 + self.vel.1*time).rem_euclid(field.1);
-
-        (px, py)
+    // Where the robot is after time seconds, wrapping around the edges of the field.
+    fn project(&self, field: (isize, isize), time: isize) -> Point {
+        (self.pos + self.vel*time).wrap(field.0, field.1)
     }
 }
 
@@ -33,23 +31,15 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let robots = text.lines()
-            .filter_map(|s| {
-                if let Some(caps) = LINE_RE.captures(s) {
-                    // println!("Captured: {}, {}, {}, {}", &caps[1], &caps[2], &caps[3], &caps[4]);
-                    let pos = ( caps[1].parse().unwrap(), caps[2].parse().unwrap() );
-                    let vel = ( caps[3].parse().unwrap(), caps[4].parse().unwrap() );
-                    Some( Robot { pos, vel })
-                }
-                else {
-                    None
-                }
-            })
-            .collect();
+        let mut robots = Vec::new();
+        for line in parse::lines(DAY, text) {
+            let [px, py, vx, vy] = line.ints_n("expected a robot like p=0,4 v=3,-3")?;
+            robots.push( Robot { pos: Point::new(px, py), vel: Vector::new(vx, vy) });
+        }
 
-        Input { robots }
+        Ok(Input { robots })
     }
 
     fn safety_factor(&self, field: (isize, isize), time: isize) -> usize {
@@ -57,19 +47,19 @@ impl Input {
         let components: (isize, isize, isize, isize) = self.robots.iter()
             .map(|r| { 
                 let dest = r.project(field, time);
-                if (dest.0 < mid.0) & (dest.1 < mid.1) {
+                if (dest.x < mid.0) & (dest.y < mid.1) {
                     // Upper left quadrant
                     (1, 0, 0, 0)
                 }
-                else if (dest.0 < mid.0) & (dest.1 > mid.1) {
+                else if (dest.x < mid.0) & (dest.y > mid.1) {
                     // Lower left quadrant
                     (0, 1, 0, 0)
                 }
-                else if (dest.0 > mid.0) & (dest.1 < mid.1) {
+                else if (dest.x > mid.0) & (dest.y < mid.1) {
                     // Upper right quadrant
                     (0, 0, 1, 0)
                 }
-                else if (dest.0 > mid.0) & (dest.1 > mid.1) {
+                else if (dest.x > mid.0) & (dest.y > mid.1) {
                     // Lower right quadrant
                     (0, 0, 0, 1)
                 }
@@ -106,17 +96,12 @@ impl Input {
     // The tree pattern has this, no other state does.
     fn is_tree(&self, field: (isize, isize), time: isize) -> bool {
         // Look for a horizontal line segment 30 elements long
-        let mut map: HashSet<(isize, isize)> = HashSet::new();
-
-        for r in self.robots.iter() {
-            let dest = r.project(field, time);
-            map.insert(dest);
-        }
+        let map = self.positions(field, time);
 
         for line_no in 0..field.1 {
             'line: for col_no in 0..field.0-30 {
                 for n in 0..30 {
-                    if !map.contains(&(col_no+n, line_no)) {
+                    if !map.contains(&Point::new(col_no+n, line_no)) {
                         continue 'line;
                     }
                 }
@@ -131,29 +116,24 @@ impl Input {
 
     }
 
-    // fn show_tree(&self, field: (isize, isize), time: isize) {
-    //     let mut map: HashSet<(isize, isize)> = HashSet::new();
-
-    //     for r in self.robots.iter() {
-    //         let dest = r.project(field, time);
-    //         map.insert(dest);
-    //     }
-
-    //     for line_no in 0..field.1 {
-    //         for col_no in 0..field.0 {
-    //             if map.contains(&(col_no, line_no)) {
-    //                 print!("#");
-    //             }
-    //             else {
-    //                 print!(".");
-    //             }
-    //         }
-    //         println!();
-    //     }
-    //     println!();
-    // }
+    // Where the robots are at this time.
+    fn positions(&self, field: (isize, isize), time: isize) -> HashSet<Point> {
+        self.robots.iter().map(|r| r.project(field, time)).collect()
+    }
+
+    // The field at this time, with the robots on it.
+    fn frame(&self, field: (isize, isize), time: isize) -> Frame {
+        let mut cells = Grid::new(field.0 as usize, field.1 as usize, Cell::new('.', viz::BACKGROUND));
+        for pos in self.positions(field, time).iter().filter_map(|p| p.to_pos()) {
+            cells[pos] = Cell::new('#', viz::START);
+        }
+
+        Frame::new(&format!("after {time} seconds"), cells)
+    }
 }
 
+solver!(Day14, year: 2024, day: 14, title: "Restroom Redoubt", tags: ["simulation", "geometry"]);
+
 pub struct Day14 {
 }
 
@@ -162,21 +142,58 @@ impl Day14 {
     pub const fn new() -> Self {
         Self { }
     }
+
+    // The field's width and height.
+    fn field(params: &Params) -> Result<(isize, isize), SolveError> {
+        Ok((params.positive(DAY, "width")? as isize, params.positive(DAY, "height")? as isize))
+    }
 }
 
 impl<'a> Day for Day14 {
 
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param { name: "width", default: Value::Unsigned(101), help: "width of the field" },
+            Param { name: "height", default: Value::Unsigned(103), help: "height of the field" },
+            Param { name: "seconds", default: Value::Unsigned(100), help: "time to run in part 1" },
+            Param { name: "limit", default: Value::Unsigned(101*103), help: "longest time to search for the tree in part 2" },
+        ]
+    }
+
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let field = Day14::field(params)?;
+        let input = timing::parse(|| Input::read(text))?;
+
+        Ok(Answer::Numeric(input.safety_factor(field, params.usize("seconds") as isize)))
+    }
+
+    fn part2(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let field = Day14::field(params)?;
+        let input = timing::parse(|| Input::read(text))?;
+
+        Ok(Answer::Numeric(input.tree_search(field, params.usize("limit") as isize)))
+    }
 
-        Answer::Numeric(input.safety_factor((101, 103), 100))
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
+}
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+impl Visualize for Day14 {
+    // The robots after part 1's time, then when they draw the tree.
+    fn frames(&self, text: &str, params: &Params) -> Result<Vec<Frame>, SolveError> {
+        let field = Day14::field(params)?;
+        let input = Input::read(text)?;
+        let limit = params.usize("limit") as isize;
+
+        let mut frames = vec![input.frame(field, params.usize("seconds") as isize)];
+        let tree = input.tree_search(field, limit) as isize;
+        if tree <= limit {
+            frames.push(input.frame(field, tree));
+        }
 
-        Answer::Numeric(input.tree_search((101, 103), 101*103))
+        Ok(frames)
     }
 }
 
@@ -185,8 +202,13 @@ impl<'a> Day for Day14 {
 mod test {
 
     use crate::day14::{Day14, Input};
+    use crate::geom::{Point, Vector};
     use crate::day::{Day, Answer};
-    use data_aoc2024::DAY14_INPUT;
+    use crate::params::Params;
+    use crate::error::{ParseError, SolveError};
+    use crate::viz::Visualize;
+    #[cfg(feature = "embedded")]
+    use crate::inputs::embedded_input;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -207,41 +229,65 @@ p=9,5 v=-3,-3
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.robots.len(), 12);
-        assert_eq!(input.robots[0].pos, (0, 4));
-        assert_eq!(input.robots[0].vel, (3, -3));
+        assert_eq!(input.robots[0].pos, Point::new(0, 4));
+        assert_eq!(input.robots[0].vel, Vector::new(3, -3));
+    }
+
+    #[test]
+    // A robot that doesn't parse is reported, rather than skipped.
+    fn test_read_error() {
+        let result = Input::read("p=0,4 v=3,-3\np=6,3 v=-1\n");
+
+        assert_eq!(result.err(), Some(ParseError::new(14, 2, 1, "p=6,3 v=-1", "expected a robot like p=0,4 v=3,-3")));
     }
 
     #[test]
     // Read and confirm inputs
     fn test_projection() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.robots[10].project((11, 7), 1), (4, 1));
-        assert_eq!(input.robots[10].project((11, 7), 2), (6, 5));
-        assert_eq!(input.robots[10].project((11, 7), 3), (8, 2));
-        assert_eq!(input.robots[10].project((11, 7), 4), (10, 6));
-        assert_eq!(input.robots[10].project((11, 7), 5), (1, 3));
+        assert_eq!(input.robots[10].project((11, 7), 1), Point::new(4, 1));
+        assert_eq!(input.robots[10].project((11, 7), 2), Point::new(6, 5));
+        assert_eq!(input.robots[10].project((11, 7), 3), Point::new(8, 2));
+        assert_eq!(input.robots[10].project((11, 7), 4), Point::new(10, 6));
+        assert_eq!(input.robots[10].project((11, 7), 5), Point::new(1, 3));
     }
 
     
     #[test]
     // Read and confirm inputs
     fn test_safety_factor() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.safety_factor((11, 7), 100), 12);
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_tree_time() {
-        let input = Input::read(DAY14_INPUT);
+        let Some(text) = embedded_input(14) else {
+            println!("Skipping day 14: no embedded input");
+            return;
+        };
+        let input = Input::read(text).unwrap();
 
         assert_eq!(input.tree_search((101, 103), 10403), 6620);
+    }
 
-        // input.show_tree((101, 103), 2697);
+    #[test]
+    fn test_frames() {
+        // No tree turns up on the example's field.
+        let d = Day14::new();
+        let params = Params::new(d.params(), &[("width", "11"), ("height", "7"), ("limit", "10")]).unwrap();
+        let frames = d.frames(EXAMPLE1, &params).unwrap();
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].caption, "after 100 seconds");
+        assert_eq!(frames[0].text().lines().next(), Some("......#..#."));
+        assert_eq!(frames[0].text().matches('#').count(), 10);
     }
 
     #[test]
@@ -249,15 +295,30 @@ p=9,5 v=-3,-3
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day14::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(21));
+        assert_eq!(d.part1(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(21)));
+
+        // The example's field is smaller.
+        let params = Params::new(d.params(), &[("width", "11"), ("height", "7")]).unwrap();
+        assert_eq!(d.part1(EXAMPLE1, &params), Ok(Answer::Numeric(12)));
+
+        for (width, height) in [("0", "7"), ("11", "0")] {
+            let params = Params::new(d.params(), &[("width", width), ("height", height)]).unwrap();
+            assert!(matches!(d.part1(EXAMPLE1, &params), Err(SolveError::BadInput { .. })));
+            assert!(matches!(d.part2(EXAMPLE1, &params), Err(SolveError::BadInput { .. })));
+        }
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
+        let Some(text) = embedded_input(14) else {
+            println!("Skipping day 14: no embedded input");
+            return;
+        };
         // Based on the example in part 2.
         let d = Day14::new();
-        assert_eq!(d.part2(data_aoc2024::DAY14_INPUT), Answer::Numeric(6620)); // < 10402
+        assert_eq!(d.part2(text, &Params::defaults(d.params())), Ok(Answer::Numeric(6620))); // < 10402
     }
     
 }
\ No newline at end of file
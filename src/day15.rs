@@ -1,11 +1,15 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}};
+use std::{cmp::Ordering, collections::HashSet};
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::geom::{Dir4, Point};
+use crate::grid::{Grid, Pos};
+use crate::timing;
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::registry::solver;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Dir {
-    N,E,S,W,
-}
+const DAY: usize = 15;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum MapState {
@@ -18,76 +22,49 @@ enum MapState {
 }
 
 struct Board {
-    board: Vec<Vec<MapState>>,
-    pos: (isize, isize),
+    board: Grid<MapState>,
+    pos: Pos,
 }
 
 impl Board {
     fn new(input: &Input, part2: bool) -> Board {
-        let mut board = Vec::new();
-
-        for y in 0..input.size.1 {
-            let mut row = Vec::new();
-            for x in 0..input.size.0 {
-                if part2 {
-                    // Each input cell, becomes two board cells.
-                    match input.map[&(x, y)] {
-                        MapState::Box => {
-                            row.push(MapState::BoxLeft);
-                            row.push(MapState::BoxRight);
-                        }
-                        MapState::Wall => {
-                            row.push(MapState::Wall);
-                            row.push(MapState::Wall);
-                        }
-                        MapState::Empty => {
-                            row.push(MapState::Empty);
-                            row.push(MapState::Empty);
-                        }
-                        MapState::Player => {
-                            row.push(MapState::Player);
-                            row.push(MapState::Empty);
-                        }
-                        _ => {
-                            // Other map states don't occur in input.
-                            assert!(false, "Bad map state in input.");
-                        }
-                    }
-                }
-                else {
-                    // Simply copy map state from the input map to the board
-                    row.push(input.map[&(x, y)]);
-                }
-                
-            }
-            board.push(row);
+        if !part2 {
+            // Simply copy the input map to the board
+            return Board { board: input.map.clone(), pos: input.start_pos };
         }
 
-        let pos = if part2 {
-            // part 2: adjust start position for double-wide processing.
-            (input.start_pos.0*2, input.start_pos.1)
+        // part 2: each input cell becomes two board cells.
+        let mut board = Grid::new(input.map.width()*2, input.map.height(), MapState::Empty);
+        for ((x, y), state) in input.map.iter() {
+            let (left, right) = match state {
+                MapState::Box => (MapState::BoxLeft, MapState::BoxRight),
+                MapState::Wall => (MapState::Wall, MapState::Wall),
+                MapState::Empty => (MapState::Empty, MapState::Empty),
+                MapState::Player => (MapState::Player, MapState::Empty),
+                _ => {
+                    // Other map states don't occur in input.
+                    panic!("Bad map state in input.");
+                }
+            };
+            board[(x*2, y)] = left;
+            board[(x*2+1, y)] = right;
         }
-        else {
-            // part 1: use given start position
-            input.start_pos
-        };
+
+        // adjust start position for double-wide processing.
+        let pos = (input.start_pos.0*2, input.start_pos.1);
 
         Board { board, pos }
     }
 
-    // return coord of adjacent position in the given direction
-    fn adjacent_to(a: &(isize, isize), dir: &Dir) -> (isize, isize) {
-        match dir {
-            Dir::N => { (a.0, a.1-1) }
-            Dir::S => { (a.0, a.1+1) }
-            Dir::E => { (a.0+1, a.1) }
-            Dir::W => { (a.0-1, a.1) }
-        }
+    // return coord of adjacent position in the given direction.  The warehouse is
+    // walled in, so nothing moves off the board.
+    fn adjacent_to(a: &Pos, dir: &Dir4) -> Pos {
+        (Point::from(*a) + dir.delta()).to_pos().expect("moved off the board")
     }
 
-    fn compare_move(a: &(isize, isize), b: &(isize, isize), dir: &Dir) -> Ordering {
+    fn compare_move(a: &Pos, b: &Pos, dir: &Dir4) -> Ordering {
         match dir {
-            Dir::N => {
+            Dir4::N => {
                 // When moving North, low-y pieces sort first.
                 if a.1 < b.1 { return Ordering::Less; }
                 if a.1 > b.1 { return Ordering::Greater; }
@@ -97,7 +74,7 @@ impl Board {
                 if a.0 > b.0 { return Ordering::Greater; }
                 return Ordering::Equal;
             }
-            Dir::S => {
+            Dir4::S => {
                 // When moving South, high-y pieces sort first.
                 if a.1 < b.1 { return Ordering::Greater; }
                 if a.1 > b.1 { return Ordering::Less; }
@@ -107,7 +84,7 @@ impl Board {
                 if a.0 > b.0 { return Ordering::Greater; }
                 return Ordering::Equal;
             }
-            Dir::E => {
+            Dir4::E => {
                 // When moving East, high-x pieces sort first.
                 if a.0 < b.0 { return Ordering::Greater; }
                 if a.0 > b.0 { return Ordering::Less; }
@@ -117,7 +94,7 @@ impl Board {
                 if a.1 > b.1 { return Ordering::Greater; }
                 return Ordering::Equal;
             }
-            Dir::W => {
+            Dir4::W => {
                 // When moving West, low-x pieces sort first
                 if a.0 < b.0 { return Ordering::Less; }
                 if a.0 > b.0 { return Ordering::Greater; }
@@ -131,9 +108,9 @@ impl Board {
 
     }
 
-    fn do_move(&mut self, dir: &Dir) {
-        let mut to_move: Vec<(isize, isize)> = Vec::new();
-        let mut moving: HashSet<(isize, isize)> = HashSet::new();
+    fn do_move(&mut self, dir: &Dir4) {
+        let mut to_move: Vec<Pos> = Vec::new();
+        let mut moving: HashSet<Pos> = HashSet::new();
 
         // Start evaluating move from the player's position
         to_move.push(self.pos);
@@ -149,7 +126,7 @@ impl Board {
             moving.insert(checking);
 
             let moved_into = Board::adjacent_to(&checking, dir);
-            match self.board[moved_into.1 as usize][moved_into.0 as usize] {
+            match self.board[moved_into] {
                 MapState::Wall => {
                     // Blocked!  Give up.
                     return;
@@ -159,10 +136,10 @@ impl Board {
                     to_move.push(moved_into);
                 }
                 MapState::BoxLeft => {
-                    if (*dir == Dir::N) | (*dir == Dir::S) {
+                    if (*dir == Dir4::N) | (*dir == Dir4::S) {
                         // This pushes on both halves of the box.
                         to_move.push(moved_into);
-                        to_move.push(Board::adjacent_to(&moved_into, &Dir::E));
+                        to_move.push(Board::adjacent_to(&moved_into, &Dir4::E));
                     }
                     else {
                         // Moving E/W
@@ -170,10 +147,10 @@ impl Board {
                     }
                 }
                 MapState::BoxRight => {
-                    if (*dir == Dir::N) | (*dir == Dir::S) {
+                    if (*dir == Dir4::N) | (*dir == Dir4::S) {
                         // This push needs to apply to both halves of the box.
                         to_move.push(moved_into);
-                        to_move.push(Board::adjacent_to(&moved_into, &Dir::W));
+                        to_move.push(Board::adjacent_to(&moved_into, &Dir4::W));
                     }
                     else {
                         // Moving E/W
@@ -187,19 +164,19 @@ impl Board {
         }
 
         // All necessary moves are possible and stored in moving
-        let mut move_steps: Vec<&(isize, isize)> = moving.iter().collect();
+        let mut move_steps: Vec<&Pos> = moving.iter().collect();
         move_steps.sort_by(|a, b| { Board::compare_move(*a, *b, dir) });
 
         // Execute the moves
         for m in move_steps {
             let move_to = Board::adjacent_to(m, dir);
-            assert_eq!(self.board[move_to.1 as usize][move_to.0 as usize], MapState::Empty);
-            self.board[move_to.1 as usize][move_to.0 as usize] = self.board[m.1 as usize][m.0 as usize];
-            self.board[m.1 as usize][m.0 as usize] = MapState::Empty;
+            assert_eq!(self.board[move_to], MapState::Empty);
+            self.board[move_to] = self.board[*m];
+            self.board[*m] = MapState::Empty;
         }
 
         // Leave an open space where we started
-        self.board[self.pos.1 as usize][self.pos.0 as usize] = MapState::Empty;
+        self.board[self.pos] = MapState::Empty;
 
         // Update the player position
         self.pos = Board::adjacent_to(&self.pos, dir);
@@ -207,105 +184,66 @@ impl Board {
     }
 
     fn gps(&self) -> usize {
-        let mut gps = 0;
-
-        for y in 0..self.board.len() {
-            for x in 0..self.board[0].len() {
-                if (self.board[y][x] == MapState::Box) | (self.board[y][x] == MapState::BoxLeft) {
-                    gps += 100*y + x;
-                }
-            }
-        }
-
-        gps
+        self.board.iter()
+            .filter(|(_, state)| (**state == MapState::Box) | (**state == MapState::BoxLeft))
+            .map(|((x, y), _)| 100*y + x)
+            .sum()
     }
 
-    #[allow(dead_code)]
-    fn show(&self) {
-        for y in 0..self.board.len() {
-            for x in 0..self.board[0].len() {
-                match self.board[y][x] {
-                    MapState::Wall => { print!("#"); }
-                    MapState::Empty => { print!("."); }
-                    MapState::Box => { print!("O"); }
-                    MapState::BoxLeft => { print!("["); }
-                    MapState::BoxRight => { print!("]"); }
-                    MapState::Player => { print!("@"); }
-                }
-            }
-            println!();
-        }
-        println!();
+    fn frame(&self, caption: &str) -> Frame {
+        Frame::from_grid(caption, &self.board, |state| match state {
+            MapState::Wall => Cell::new('#', viz::WALL),
+            MapState::Empty => Cell::new('.', viz::BACKGROUND),
+            MapState::Box => Cell::new('O', viz::PATH),
+            MapState::BoxLeft => Cell::new('[', viz::PATH),
+            MapState::BoxRight => Cell::new(']', viz::PATH),
+            MapState::Player => Cell::new('@', viz::START),
+        })
     }
 }
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
+// A representation of the puzzle inputs: the warehouse map, then the moves.
 struct Input {
-    map: HashMap<(isize, isize), MapState>,
-    size: (isize, isize),
-    moves: Vec<Dir>,
-    start_pos: (isize, isize),
+    map: Grid<MapState>,
+    moves: Vec<Dir4>,
+    start_pos: Pos,
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut map = HashMap::new();
-        let mut size = (0, 0);
+        // The map runs up to the first blank line.
+        let map = Grid::parse(DAY, text, |c| match c {
+            '.' => Ok(MapState::Empty),
+            '#' => Ok(MapState::Wall),
+            'O' => Ok(MapState::Box),
+            '@' => Ok(MapState::Player),
+            _ => Err("unexpected map character"),
+        })?;
+        let start_pos = map.find(&MapState::Player).unwrap_or((0, 0));
+
+        // Process the lines of moves, after the blank line
         let mut moves = Vec::new();
-        let mut in_map = true;
-        let mut y = 0;
-        let mut x = 0;
-        let mut start_pos = (0, 0);
-
-        for line in text.lines() {
-            if in_map {
-                if line.trim().len() == 0 {
-                    // Switch to processing moves
-                    in_map = false;
-                }
-                else {
-                    // Process a map line
-                    for c in line.chars() {
-                        match c {
-                            '.' => { map.insert((x, y), MapState::Empty); }
-                            '#' => { map.insert((x, y), MapState::Wall); }
-                            'O' => { map.insert((x, y), MapState::Box); }
-                            '@' => { 
-                                map.insert((x, y), MapState::Player); 
-                                start_pos = (x, y);
-                            }
-                            _ => ()
-                        }
-                        x += 1;
-                        if x > size.0 { size.0 = x; }
-                    }
-
-                    x = 0;
-                    y += 1; 
-                    if y > size.1 { size.1 = y; }
-                }
-            }
-            else {
-                // Process a line of moves
-                for c in line.chars() {
-                    match c {
-                        '^' => { moves.push(Dir::N); }
-                        '>' => { moves.push(Dir::E); }
-                        'v' => { moves.push(Dir::S); }
-                        '<' => { moves.push(Dir::W); }                        
-                        _ => ()
+        for (idx, line) in text.lines().enumerate().skip(map.height()+1) {
+            for (pos, c) in line.trim_end().char_indices() {
+                match c {
+                    '^' => { moves.push(Dir4::N); }
+                    '>' => { moves.push(Dir4::E); }
+                    'v' => { moves.push(Dir4::S); }
+                    '<' => { moves.push(Dir4::W); }                        
+                    _ => {
+                        return Err(ParseError::at_char(DAY, idx, line, pos, "expected a move"));
                     }
                 }
             }
         }
 
-
-        Input { map, size, moves, start_pos }
+        Ok(Input { map, moves, start_pos })
     }
 }
 
+solver!(Day15, year: 2024, day: 15, title: "Warehouse Woes", tags: ["grid", "simulation"]);
+
 pub struct Day15 {
 }
 
@@ -319,26 +257,49 @@ impl Day15 {
 impl<'a> Day for Day15 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
         let mut board = Board::new(&input, false);
 
         for m in input.moves {
             board.do_move(&m);
         }
 
-        Answer::Numeric(board.gps())
+        Ok(Answer::Numeric(board.gps()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
         let mut board = Board::new(&input, true);
 
         for m in input.moves {
             board.do_move(&m);
         }
 
-        Answer::Numeric(board.gps())
+        Ok(Answer::Numeric(board.gps()))
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+impl Visualize for Day15 {
+    // The warehouse before and after the robot's moves, in each part.
+    fn frames(&self, text: &str, _params: &Params) -> Result<Vec<Frame>, SolveError> {
+        let input = Input::read(text)?;
+
+        let mut frames = Vec::new();
+        for (part, wide) in [(1, false), (2, true)] {
+            let mut board = Board::new(&input, wide);
+            frames.push(board.frame(&format!("part {part}, the warehouse")));
+            for m in input.moves.iter() {
+                board.do_move(m);
+            }
+            frames.push(board.frame(&format!("part {part}, after {} moves", input.moves.len())));
+        }
+
+        Ok(frames)
     }
 }
 
@@ -346,8 +307,11 @@ impl<'a> Day for Day15 {
 
 mod test {
 
-    use crate::day15::{Day15, Input, Board, Dir};
+    use crate::day15::{Day15, Input, Board};
+    use crate::geom::Dir4;
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::viz::Visualize;
     
     // Example Input
     const EXAMPLE1: &str = "\
@@ -377,7 +341,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.moves.len(), 70*10);
         assert_eq!(input.start_pos, (4, 4));
@@ -385,153 +349,153 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
 
     #[test]
     fn test_board() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let board = Board::new(&input, false);
 
-        assert_eq!(board.board.len(), 10);
-        assert_eq!(board.board[0].len(), 10);
+        assert_eq!(board.board.height(), 10);
+        assert_eq!(board.board.width(), 10);
         assert_eq!(board.pos, (4, 4));
     }
 
     #[test]
     fn test_board2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let board = Board::new(&input, true);
 
-        assert_eq!(board.board.len(), 10);
-        assert_eq!(board.board[0].len(), 20);
+        assert_eq!(board.board.height(), 10);
+        assert_eq!(board.board.width(), 20);
         assert_eq!(board.pos, (8, 4));
     }
 
     #[test]
     fn test_moves() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, false);
 
         assert_eq!(board.pos, (4, 4));
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (3, 4));
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (2, 4));
 
         // Now it's blocked.
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (2, 4));
     }
 
     #[test]
     fn test_moves_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, true);
 
         // starting position
         assert_eq!(board.pos, (8, 4));
 
         // Move once
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (7, 4));
 
         // Three more moves are OK.
-        board.do_move(&Dir::W);
-        board.do_move(&Dir::W);
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
+        board.do_move(&Dir4::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (4, 4));
 
         // Now it's blocked.
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (4, 4));
     }
 
     #[test]
     fn test_moves2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, false);
 
         assert_eq!(board.pos, (4, 4));
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (4, 3));
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (3, 3));
 
         // Stuck now
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (3, 3));
     }
 
     
     #[test]
     fn test_moves2_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, true);
 
         // Confirm starting position
         assert_eq!(board.pos, (8, 4));
 
         // Move North
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (8, 3));
 
         // Two moves west are OK
-        board.do_move(&Dir::W);
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (6, 3));
 
         // Now blocked from moving West
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (6, 3));
     }
     
     #[test]
     fn test_moves3_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, true);
 
         // Confirm starting position
         assert_eq!(board.pos, (8, 4));
 
         // Move South, West
-        board.do_move(&Dir::S);
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::S);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (7, 5));
 
         // One move north is OK
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (7, 4));
 
         // Now blocked from moving North
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (7, 4));
     }
         
     #[test]
     fn test_moves4_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, true);
 
         // Confirm starting position
         assert_eq!(board.pos, (8, 4));
 
         // One move West, misaligning blocks
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (7, 4));
 
         // Move South, West
-        board.do_move(&Dir::S);
-        board.do_move(&Dir::W);
+        board.do_move(&Dir4::S);
+        board.do_move(&Dir4::W);
         assert_eq!(board.pos, (6, 5));
 
         // One move north is OK
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (6, 4));
 
         // Now blocked from moving North
-        board.do_move(&Dir::N);
+        board.do_move(&Dir4::N);
         assert_eq!(board.pos, (6, 4));
     }
 
     #[test]
     fn test_ex1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, false);
 
         for m in input.moves {
@@ -545,7 +509,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
     
     #[test]
     fn test_ex1_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut board = Board::new(&input, true);
 
         for m in input.moves {
@@ -555,12 +519,32 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
         assert_eq!(board.gps(), 9021);
     }
 
+    #[test]
+    fn test_frames() {
+        let frames = Day15::new().frames(EXAMPLE1, &Params::default()).unwrap();
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[1].text(), "\
+##########
+#.O.O.OOO#
+#........#
+#OO......#
+#OO@.....#
+#O#.....O#
+#O.....OO#
+#O.....OO#
+#OO....OO#
+##########
+");
+        assert_eq!(frames[2].cells.width(), 20);
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day15::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(10092));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(10092)));
     }
 
     #[test]
@@ -568,7 +552,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day15::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(9021));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(9021)));
     }
     
 }
\ No newline at end of file
@@ -1,265 +1,120 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use crate::day::{Day, Answer};
-use priority_queue::PriorityQueue;
-
-#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
-enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::geom::{Dir4, Point};
+use crate::grid::Grid;
+use crate::search;
+use crate::timing;
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::registry::solver;
+
+const DAY: usize = 16;
 
 #[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
 struct SolnState {
     pos: (usize, usize),
-    dir: Dir,
+    dir: Dir4,
 }
 
 
 impl SolnState {
     fn new(pos: (usize, usize)) -> SolnState {
-        SolnState {pos, dir: Dir::E }
+        SolnState {pos, dir: Dir4::E }
     }
 
-    fn forward(&self) -> SolnState {
-        let newpos = match self.dir {
-            Dir::N => { (self.pos.0, self.pos.1-1) },
-            Dir::S => { (self.pos.0, self.pos.1+1) },
-            Dir::E => { (self.pos.0+1, self.pos.1) },
-            Dir::W => { (self.pos.0-1, self.pos.1) },
-        };
+    // The step forward, unless it leaves the top or left edge of the grid.
+    fn forward(&self) -> Option<SolnState> {
+        let newpos = (Point::from(self.pos) + self.dir.delta()).to_pos()?;
 
-        SolnState {pos: newpos, dir: self.dir}
+        Some(SolnState {pos: newpos, dir: self.dir})
     }
 
     fn right(&self) -> SolnState {
-        let new_dir = match self.dir {
-            Dir::N => { Dir::E },
-            Dir::S => { Dir::W },
-            Dir::E => { Dir::S },
-            Dir::W => { Dir::N },
-        };
-
-        SolnState {pos: self.pos, dir: new_dir}
+        SolnState {pos: self.pos, dir: self.dir.turn_right()}
     }
 
     fn left(&self) -> SolnState {
-        let new_dir = match self.dir {
-            Dir::N => { Dir::W },
-            Dir::S => { Dir::E },
-            Dir::E => { Dir::N },
-            Dir::W => { Dir::S },
-        };
-
-        SolnState {pos: self.pos, dir: new_dir}
+        SolnState {pos: self.pos, dir: self.dir.turn_left()}
     }
 }
 
 // A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
 struct Input {
-    maze: HashSet<(usize, usize)>, // A coord in maze represents open space.
-    start: (usize, usize),         // Start location
-    end: (usize, usize),           // End location
+    maze: Grid<bool>,      // true where the maze is open space.
+    start: (usize, usize), // Start location
+    end: (usize, usize),   // End location
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut maze = HashSet::new();
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-
-        for (y, line) in text.lines().enumerate() {
-            let line = line.trim();
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    '#' => {
-                        // Wall, we don't need to do anything
-                    }
-                    '.' => {
-                        // Open space, add this to the maze HashSet
-                        maze.insert((x, y));
-                    }
-                    'S' => {
-                        // Start, add this to the maze HashSet and record start
-                        maze.insert((x, y));
-                        start = (x, y);
-                    }
-                    'E' => {
-                        // End, add to maze and record end
-                        maze.insert((x, y));
-                        end = (x, y);
-                    }
-                    _ => {
-                        // Invalid input
-                        panic!("Invalid input character.");
-                    }
-                }
-            }
-        }
+        let map = Grid::parse(DAY, text, |c| match c {
+            '#' | '.' | 'S' | 'E' => Ok(c),
+            _ => Err("unexpected maze character"),
+        })?;
 
-        Input { maze, start, end }
+        let start = map.find(&'S').unwrap_or((0, 0));
+        let end = map.find(&'E').unwrap_or((0, 0));
+        let maze = map.map(|c| *c != '#');
+
+        Ok(Input { maze, start, end })
     }
 
     fn solve_best_score(&self) -> Option<usize> {
-        let mut to_explore: PriorityQueue<(SolnState, Vec<(usize, usize)>), isize> = PriorityQueue::new();
-        let mut explored: HashMap<SolnState, usize> = HashMap::new();
         let start_state = SolnState::new(self.start);
-        to_explore.push((start_state, Vec::new()), 0);
-        let mut best_cost = None;
-
-        while !to_explore.is_empty() {
-            // Pop the best current state from the queue
-            let ((current, path), neg_cost) = to_explore.pop().unwrap();
-            let cost = -neg_cost as usize;
-
-            if let Some(old_cost) = explored.get(&current) {
-                if *old_cost <= cost {
-                    // We already explored this state at a lower cost.
-                    continue;
-                }
-            }
-
-            explored.insert(current, cost);
-
-            // If this is at the end, we have solved it.
-            if current.pos == self.end {
-                best_cost = Some(cost);
-                break;
-            }
-
-            // Generate all possible next steps from here and their costs
-            for (next_state, next_cost) in Self::next_states(&current, &cost) {
-                if self.maze.contains(&next_state.pos) {
-                    // The space is clear
-                    // next_state represents a valid move
-                    // extend path
-                    let mut extended_path = path.clone();
-                    extended_path.push(next_state.pos);
-                    to_explore.push((next_state, extended_path), -(next_cost as isize));
-                }
-            }
-        }
 
-        best_cost
+        search::dijkstra_path(start_state, |state| self.next_states(state), |state| state.pos == self.end)
+            .map(|(_path, cost)| cost)
     }
 
     fn solve_num_tiles(&self) -> Option<usize> {
-        // Each entry in to_explore contains a SolnState and a location this state was reached from.
-        let mut to_explore: PriorityQueue<(SolnState, SolnState), isize> = PriorityQueue::new();
-        
-        let mut explored: HashMap<SolnState, usize> = HashMap::new();
+        self.best_tiles().map(|tiles| tiles.len())
+    }
+
+    // The tiles on any of the best paths.
+    fn best_tiles(&self) -> Option<HashSet<(usize, usize)>> {
+        // Find every best path, then collect all the tiles their states occupied.
         let start_state = SolnState::new(self.start);
-        to_explore.push((start_state, start_state), 0);
-
-        let mut best_cost = None;
-
-        // For every state, this records a set of states that lead to it with lowest score.
-        let mut comes_from: HashMap<SolnState, HashSet<SolnState>> = HashMap::new();
-
-        while !to_explore.is_empty() {
-            // Pop the best current state from the queue
-            let ((current, came_from), neg_cost) = to_explore.pop().unwrap();
-            let cost = -neg_cost as usize;
-
-            if let Some(known_cost) = best_cost {
-                if known_cost < cost {
-                    // We're done exploring when the costs get higher
-                    // than the known best cost.
-                    break;
-                }
-            }
-
-            if let Some(old_cost) = explored.get(&current) {
-                // We've been to this state on other paths
-                if cost > *old_cost {
-                    // This is a worse way to reach this state than we already know.
-                    // Stop exploring this path
-                    continue;
-                }
-
-                let old_set = comes_from.get_mut(&current).unwrap();
-                if cost < *old_cost {
-                    // This is a better way to reach this tile, reset the paths_to info
-                    old_set.clear();
-                }
-
-                // Add the current path's info to this tile's paths_to
-                old_set.insert(came_from);
-            }
-            else {
-                // First time visiting this state
-                // Register this tile with paths_to data structure
-                let mut new_set = HashSet::new();
-                new_set.insert(came_from);
-                comes_from.insert(current, new_set);
-            }
-
-            explored.insert(current, cost);
-
-            // If not at the end, keep exploring
-            if current.pos != self.end {
-                // Generate all possible next steps from here and their costs
-                for (next_state, next_cost) in Self::next_states(&current, &cost) {
-                    if self.maze.contains(&next_state.pos) {
-                        // The space is clear, next_state is a valid move.
-                        to_explore.push((next_state, current), -(next_cost as isize));
-                    }
-                }
-            }
-            else {
-                // We are at the end and now know the best cost.
-                best_cost = Some(cost);
-            }
-        }
+        let paths = search::dijkstra_all(start_state, |state| self.next_states(state), |state| state.pos == self.end)?;
+
+        let best_tiles: HashSet<(usize, usize)> = paths.states().iter()
+            .map(|state| state.pos)
+            .collect();
 
-        match best_cost {
-            Some(_cost) => {
-                // We have a solution, return cost and num best_tiles.
-                // Work through all the states we passed through, collect all the tiles they occupied.
-                let mut best_tiles: HashSet<(usize, usize)> = HashSet::new();
-                let mut backtrack_states_to_explore: Vec<SolnState> = Vec::new();
-                backtrack_states_to_explore.push(SolnState {pos: self.end, dir: Dir::N});
-                backtrack_states_to_explore.push(SolnState {pos: self.end, dir: Dir::S});
-                backtrack_states_to_explore.push(SolnState {pos: self.end, dir: Dir::E});
-                backtrack_states_to_explore.push(SolnState {pos: self.end, dir: Dir::W});
-                
-                let mut backtrack_explored: HashSet<SolnState> = HashSet::new();
-
-                while let Some(state) = backtrack_states_to_explore.pop() {
-                    if !backtrack_explored.contains(&state) {
-                        // this is new.
-                        backtrack_explored.insert(state);
-
-                        if let Some(set) = comes_from.get(&state) {
-                            // Make sure this tile is in best
-                            best_tiles.insert(state.pos);
-
-                            // Add all the states this comes from to the set to explore
-                            for member in set.iter() {
-                                backtrack_states_to_explore.push(*member);
-                            }
-                        }
-                    }
-                }
-
-                Some( best_tiles.len() )
-            }
-            None => { None }
+        Some( best_tiles )
+    }
+
+    // The maze, with the tiles on the best paths marked.
+    fn frame(&self, tiles: &HashSet<(usize, usize)>) -> Frame {
+        let mut cells = self.maze.map(|open| match open {
+            true => Cell::new('.', viz::BACKGROUND),
+            false => Cell::new('#', viz::WALL),
+        });
+        for pos in tiles {
+            cells[*pos] = Cell::new('O', viz::PATH);
         }
+        cells[self.start] = Cell::new('S', viz::START);
+        cells[self.end] = Cell::new('E', viz::HIGHLIGHT);
+
+        Frame::new(&format!("{} tiles on the best paths", tiles.len()), cells)
     }
 
-    // Generate next states.
-    fn next_states(state: &SolnState, cost: &usize) -> Vec<(SolnState, usize)> {
-        vec![(state.forward(), cost+1), 
-             (state.left(), cost+1000), 
-             (state.right(), cost+1000)]
+    // Generate next states that stay in the maze, and the cost of each move.
+    fn next_states(&self, state: &SolnState) -> Vec<(SolnState, usize)> {
+        [state.forward().map(|next| (next, 1)),
+         Some((state.left(), 1000)),
+         Some((state.right(), 1000))]
+            .into_iter()
+            .flatten()
+            .filter(|(next, _cost)| self.maze.get(next.pos) == Some(&true))
+            .collect()
     }
 }
 
+solver!(Day16, year: 2024, day: 16, title: "Reindeer Maze", tags: ["grid", "dijkstra"]);
+
 pub struct Day16 {
 }
 
@@ -273,24 +128,37 @@ impl Day16 {
 impl<'a> Day for Day16 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         match input.solve_best_score() {
-            Some(cost) => Answer::Numeric(cost),
-            None => Answer::None,
+            Some(cost) => Ok(Answer::Numeric(cost)),
+            None => Ok(Answer::None),
         }
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
 
         match input.solve_num_tiles() {
-            Some(best_tiles) => Answer::Numeric(best_tiles),
-            None => Answer::None,
+            Some(best_tiles) => Ok(Answer::Numeric(best_tiles)),
+            None => Ok(Answer::None),
         }
     }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+impl Visualize for Day16 {
+    fn frames(&self, text: &str, _params: &Params) -> Result<Vec<Frame>, SolveError> {
+        let input = Input::read(text)?;
+        let tiles = input.best_tiles().unwrap_or_default();
+
+        Ok(vec![input.frame(&tiles)])
+    }
 }
 
 #[cfg(test)]
@@ -299,6 +167,8 @@ mod test {
 
     use crate::day16::{Day16, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::viz::Visualize;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -342,10 +212,10 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert!(input.maze.contains(&(1, 1)));
-        assert!(!input.maze.contains(&(0, 0)));
+        assert!(input.maze[(1, 1)]);
+        assert!(!input.maze[(0, 0)]);
         assert_eq!(input.start, (1, 13));
         assert_eq!(input.end, (13, 1));
     }
@@ -353,10 +223,10 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_read_ex2() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
-        assert!(input.maze.contains(&(1, 1)));
-        assert!(!input.maze.contains(&(0, 0)));
+        assert!(input.maze[(1, 1)]);
+        assert!(!input.maze[(0, 0)]);
         assert_eq!(input.start, (1, 15));
         assert_eq!(input.end, (15, 1));
     }
@@ -364,7 +234,7 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_solve_best_score() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.solve_best_score(), Some(7036));
     }
@@ -372,7 +242,7 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_solve_num_tiles() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.solve_num_tiles(), Some(45));
     }
@@ -380,7 +250,7 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_solve_best_score_ex2() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
         assert_eq!(input.solve_best_score(), Some(11048));
     }
@@ -389,17 +259,34 @@ const EXAMPLE2: &str = "\
     #[test]
     // Read and confirm inputs
     fn test_solve_num_tiles_ex2() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
         assert_eq!(input.solve_num_tiles(), Some(64));
     }
 
+    #[test]
+    fn test_frames() {
+        let frames = Day16::new().frames(EXAMPLE1, &Params::default()).unwrap();
+        let text = frames[0].text();
+
+        assert_eq!(frames[0].caption, "45 tiles on the best paths");
+        assert_eq!(text.matches('O').count(), 43);
+        assert_eq!(text.lines().nth(13), Some("#S..#.....#OOO#"));
+        assert_eq!(text.lines().nth(1), Some("#.......#....E#"));
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day16::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(7036));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(7036)));
+
+        // Without a wall around it, the edge of the map is the edge of the maze.
+        assert_eq!(d.part1("E..\nS..\n", &Params::default()), Ok(Answer::Numeric(1001)));
+        assert_eq!(d.part2("E..\nS..\n", &Params::default()), Ok(Answer::Numeric(2)));
+        assert_eq!(d.part1("S..\n..E\n", &Params::default()), Ok(Answer::Numeric(1003)));
+        assert_eq!(d.part2("S..\n..E\n", &Params::default()), Ok(Answer::Numeric(4)));
     }
 
     #[test]
@@ -407,7 +294,7 @@ const EXAMPLE2: &str = "\
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day16::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(45));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(45)));
     }
     
 }
\ No newline at end of file
@@ -1,18 +1,13 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::{collections::{BTreeSet, HashMap}, fs, io::{self, BufRead, Write}, ops::Range, sync::{Mutex, atomic::{AtomicUsize, Ordering}}, thread};
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref REG_A_RE: Regex = Regex::new("Register A: (\\d+)").unwrap();
-    static ref REG_B_RE: Regex = Regex::new("Register B: (\\d+)").unwrap();
-    static ref REG_C_RE: Regex = Regex::new("Register C: (\\d+)").unwrap();
-    static ref PROG_RE: Regex  = Regex::new("Program: (\\d(,\\d)*)").unwrap();
-    static ref INT_RE: Regex   = Regex::new("(\\d+)").unwrap();
-}
+const DAY: usize = 17;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -24,32 +19,189 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut a = 0;
         let mut b = 0;
         let mut c = 0;
         let mut program = Vec::new();
-        for line in text.lines() {
-            if let Some(caps) = REG_A_RE.captures(line) {
-                a = caps[1].parse::<usize>().unwrap();
-            }
-            if let Some(caps) = REG_B_RE.captures(line) {
-                b = caps[1].parse::<usize>().unwrap();
-            }
-            if let Some(caps) = REG_C_RE.captures(line) {
-                c = caps[1].parse::<usize>().unwrap();
-            }
-            if let Some(caps) = PROG_RE.captures(line) {
-                for num_cap in INT_RE.captures_iter(&caps[1]) {
-                    let num = num_cap[1].parse::<usize>().unwrap();
-                    program.push(num);
+        for line in parse::lines(DAY, text) {
+            let (key, value) = line.key_value("expected a register or program")?;
+            match key {
+                "Register A" => a = line.parse(value)?,
+                "Register B" => b = line.parse(value)?,
+                "Register C" => c = line.parse(value)?,
+                "Program" => {
+                    for field in value.split(',') {
+                        let field = field.trim();
+                        let num: usize = line.parse(field)?;
+                        if num > 7 {
+                            return Err(line.field_error(field, "not a 3-bit number"));
+                        }
+                        program.push(num);
+                    }
                 }
+                _ => return Err(line.error("expected a register or program")),
             }
         }
 
-        Input { a, b, c, program }
+        Ok(Input { a, b, c, program })
+    }
+}
+
+// The machine's instructions, in opcode order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Adv, Bxl, Bst, Jnz, Bxc, Out, Bdv, Cdv,
+}
+
+// How an instruction uses its operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Literal,
+    Combo,
+    Ignored,
+}
+
+impl Op {
+    const ALL: [Op; 8] = [Op::Adv, Op::Bxl, Op::Bst, Op::Jnz, Op::Bxc, Op::Out, Op::Bdv, Op::Cdv];
+
+    pub fn decode(opcode: usize) -> Option<Op> {
+        Op::ALL.get(opcode).copied()
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"][self as usize]
+    }
+
+    fn operand(self) -> Operand {
+        match self {
+            Op::Bxl | Op::Jnz => Operand::Literal,
+            Op::Bxc => Operand::Ignored,
+            _ => Operand::Combo,
+        }
+    }
+
+    // What the instruction does, given its operand as written.
+    fn describe(self, operand: &str) -> String {
+        match self {
+            Op::Adv => format!("A = A >> {operand}"),
+            Op::Bxl => format!("B = B ^ {operand}"),
+            Op::Bst => format!("B = {operand} & 7"),
+            Op::Jnz => format!("if A != 0 jump to {operand}"),
+            Op::Bxc => String::from("B = B ^ C"),
+            Op::Out => format!("output {operand} & 7"),
+            Op::Bdv => format!("B = A >> {operand}"),
+            Op::Cdv => format!("C = A >> {operand}"),
+        }
+    }
+}
+
+// The name of a combo operand: 0 to 3 are themselves, 4 to 6 the registers.
+fn combo_name(operand: usize) -> Option<String> {
+    match operand {
+        0..4 => Some(operand.to_string()),
+        4 => Some(String::from("A")),
+        5 => Some(String::from("B")),
+        6 => Some(String::from("C")),
+        _ => None,
+    }
+}
+
+// A listing of a program, an instruction per line with its address and what it does:
+//    0: bst A     ; B = A & 7
+// Programs the machine would reject are errors, as they would be when run.
+pub fn disassemble(program: &[usize]) -> Result<String, SolveError> {
+    let mut listing = String::new();
+    for addr in (0..program.len()).step_by(2) {
+        listing.push_str(&listing_line(program, addr)?);
+        listing.push('\n');
+    }
+
+    Ok(listing)
+}
+
+// The listing of the instruction at addr.
+fn listing_line(program: &[usize], addr: usize) -> Result<String, SolveError> {
+    let (op, operand) = instruction(program, addr)?;
+    let instruction = format!("{} {operand}", op.mnemonic());
+
+    Ok(format!("{addr:2}: {instruction:9} ; {}", op.describe(&operand)))
+}
+
+// The instruction at addr, with its operand as it's written.
+fn instruction(program: &[usize], addr: usize) -> Result<(Op, String), SolveError> {
+    let Some(&operand) = program.get(addr+1) else {
+        return Err(SolveError::bad_input(DAY, &format!("missing operand at {}", addr+1)));
+    };
+    let opcode = program[addr];
+    let Some(op) = Op::decode(opcode) else {
+        return Err(SolveError::bad_input(DAY, &format!("illegal opcode {opcode} at {addr}")));
+    };
+    let operand = match op.operand() {
+        Operand::Combo => combo_name(operand)
+            .ok_or_else(|| SolveError::bad_input(DAY, &format!("illegal combo operand {operand} at {addr}")))?,
+        _ => operand.to_string(),
+    };
+
+    Ok((op, operand))
+}
+
+// Assemble a listing written like disassemble()'s into the program's numbers.
+// Addresses and comments are optional, and bxc's unused operand may be left out.
+pub fn assemble(text: &str) -> Result<Vec<usize>, ParseError> {
+    let mut program = Vec::new();
+    for line in parse::lines(DAY, text) {
+        // Drop any comment, then any address.
+        let mut code = line.text.split(';').next().unwrap_or_default();
+        if let Some((addr, rest)) = code.split_once(':') {
+            line.parse::<usize>(addr.trim())?;
+            code = rest;
+        }
+
+        let mut words = code.split_whitespace();
+        let Some(mnemonic) = words.next() else {
+            continue;
+        };
+        let Some(op) = Op::ALL.into_iter().find(|op| op.mnemonic().eq_ignore_ascii_case(mnemonic)) else {
+            return Err(line.field_error(mnemonic, "unknown instruction"));
+        };
+
+        let operand = match (op.operand(), words.next()) {
+            (Operand::Ignored, None) => 0,
+            (_, None) => return Err(line.error("missing operand")),
+            (Operand::Combo, Some(word)) => match word.to_ascii_uppercase().as_str() {
+                "0" | "1" | "2" | "3" => line.parse(word)?,
+                "A" => 4,
+                "B" => 5,
+                "C" => 6,
+                _ => return Err(line.field_error(word, "expected a combo operand, 0 to 3, A, B or C")),
+            },
+            (_, Some(word)) => match line.parse(word)? {
+                n @ 0..8 => n,
+                _ => return Err(line.field_error(word, "not a 3-bit number")),
+            },
+        };
+        if let Some(extra) = words.next() {
+            return Err(line.field_error(extra, "unexpected text after the operand"));
+        }
+
+        program.extend([op as usize, operand]);
     }
+
+    Ok(program)
+}
+
+// The program as it's written in the puzzle input.
+pub fn program_line(program: &[usize]) -> String {
+    let values: Vec<String> = program.iter().map(|v| v.to_string()).collect();
+
+    format!("Program: {}", values.join(","))
+}
+
+// A right shift, as adv, bdv and cdv do, which leaves 0 once every bit is gone.
+fn shr(x: usize, by: usize) -> usize {
+    if by < usize::BITS as usize { x >> by } else { 0 }
 }
 
 struct Machine {
@@ -78,69 +230,78 @@ impl Machine {
     }
 
     // Evaluate combo operand
-    fn combo(&self, operand: usize) -> usize {
+    fn combo(&self, operand: usize) -> Result<usize, SolveError> {
         match operand {
-            0..4 => { operand },
-            4 => { self.a },
-            5 => { self.b },
-            6 => { self.c },
+            0..4 => { Ok(operand) },
+            4 => { Ok(self.a) },
+            5 => { Ok(self.b) },
+            6 => { Ok(self.c) },
             _ => {
-                panic!("Illegal combo operand");
+                Err(SolveError::bad_input(DAY, &format!("illegal combo operand {operand} at {}", self.ip)))
             }
         }
     }
 
-    fn step(&mut self) {
-        self.cycles += 1;
-
+    // Run one instruction.  One the machine rejects leaves it unchanged, still at
+    // that instruction, so running on reports the same fault.
+    fn step(&mut self) -> Result<(), SolveError> {
         // fetch opcode and operand
         let opcode = self.program[self.ip];
-        let operand = self.program[self.ip+1];
-        self.ip += 2;
+        let operand = match self.program.get(self.ip+1) {
+            Some(operand) => *operand,
+            None => {
+                return Err(SolveError::bad_input(DAY, &format!("missing operand at {}", self.ip+1)));
+            }
+        };
+
+        // decode opcode, and read a combo operand's value
+        let Some(op) = Op::decode(opcode) else {
+            return Err(SolveError::bad_input(DAY, &format!("illegal opcode {opcode} at {}", self.ip)));
+        };
+        let value = match op.operand() {
+            Operand::Combo => self.combo(operand)?,
+            _ => operand,
+        };
 
-        // decode opcode
-        match opcode {
-            0 => {  // ADV
-                let denom = 1 << self.combo(operand);
-                // println!("{} {} ADV: {denom}", self.cycles, self.ip);
-                self.a = self.a / denom;
+        self.cycles += 1;
+        self.ip += 2;
+        match op {
+            Op::Adv => {
+                self.a = shr(self.a, value);
             }
-            1 => {  // BXL
-                self.b = self.b ^ operand;
+            Op::Bxl => {
+                self.b = self.b ^ value;
             }
-            2 => {  // BST
-                self.b = self.combo(operand) & 7;
+            Op::Bst => {
+                self.b = value & 7;
             }
-            3 => {  // JNZ
+            Op::Jnz => {
                 if self.a != 0 {
-                    self.ip = operand;
+                    self.ip = value;
                 }
             }
-            4 => {  // BXC
+            Op::Bxc => {
                 self.b = self.b ^ self.c;
             }
-            5 => {  // OUT
-                let output = self.combo(operand) & 7;
+            Op::Out => {
                 // println!("{} {} Output: {output}", self.cycles, self.ip);
-                self.output.push(output);
+                self.output.push(value & 7);
             }
-            6 => {  // BDV
-                self.b = self.a / (1 << self.combo(operand))
+            Op::Bdv => {
+                self.b = shr(self.a, value)
             }
-            7 => {  // CDV
-                self.c = self.a / (1 << self.combo(operand))
-            }
-            _ => {
-                panic!("Illegal opcode {opcode}")
+            Op::Cdv => {
+                self.c = shr(self.a, value)
             }
         }
 
+        Ok(())
     }
 
-    fn run_to_halt(&mut self) -> String {
+    fn run_to_halt(&mut self) -> Result<String, SolveError> {
         let end = self.program.len();
         while self.ip < end {
-            self.step();
+            self.step()?;
         }
         
         // convert output to comma separated string
@@ -152,65 +313,1168 @@ impl Machine {
             out.push_str(&format!("{val}"));
         }
 
-        out
+        Ok(out)
     }
 }
 
-pub struct Day17 {
+// Solving for A.  This works for programs that loop over A a few bits at a
+// time: the last instruction is the only jump, jnz 0, each pass outputs one
+// number and shifts A right once with adv, and B and C are set before they're
+// read, so only A carries from one pass to the next.  Then the output for A is
+// one number worked out from A, followed by the output for A shifted right.  So
+// A can be built up from its top bits, matching the target from its last number
+// back, and if no choice of bits works there's no A at all.
+//
+// Programs shaped any other way are solved by symbolic execution (see Symbolic
+// below), which is slower but makes no assumptions about the program.  It only
+// gives up on programs that run too long or fork too often.
+
+// How many bits each pass of the loop shifts out of A, if the program loops that way.
+fn loop_shift(program: &[usize]) -> Result<usize, SolveError> {
+    let unsupported = |why: &str| Err(SolveError::unsupported(DAY, &format!("can't solve for A, {why}")));
+
+    let mut shift = None;
+    let mut outputs = 0;
+    let mut set = [true, false, false];  // Which of A, B and C this pass has set.
+    for addr in (0..program.len()).step_by(2) {
+        let (op, _) = instruction(program, addr)?;
+        let operand = program[addr+1];
+
+        // The registers the instruction reads, and the one it sets.
+        let combo = (4..7).contains(&operand).then(|| operand - 4);
+        let (reads, sets) = match op {
+            Op::Adv => ([Some(0), combo], Some(0)),
+            Op::Bxl => ([Some(1), None], Some(1)),
+            Op::Bst => ([combo, None], Some(1)),
+            Op::Jnz => ([Some(0), None], None),
+            Op::Bxc => ([Some(1), Some(2)], Some(1)),
+            Op::Out => ([combo, None], None),
+            Op::Bdv => ([Some(0), combo], Some(1)),
+            Op::Cdv => ([Some(0), combo], Some(2)),
+        };
+        if let Some(r) = reads.into_iter().flatten().find(|r| !set[*r]) {
+            let name = ["A", "B", "C"][r];
+            return unsupported(&format!("{name} is read before it's set, so it carries from one output to the next"));
+        }
+        if let Some(r) = sets {
+            set[r] = true;
+        }
+
+        match op {
+            Op::Jnz if addr+2 < program.len() => return unsupported("the only jump must be the last instruction"),
+            Op::Adv if shift.is_some() => return unsupported("A must be shifted just once for each output"),
+            Op::Adv if (1..4).contains(&operand) => shift = Some(operand),
+            Op::Adv => return unsupported("A must be shifted by 1, 2 or 3 bits"),
+            Op::Out => outputs += 1,
+            _ => (),
+        }
+    }
+
+    if !program.ends_with(&[3, 0]) {
+        return unsupported("the program must end with jnz 0");
+    }
+    if outputs != 1 {
+        return unsupported("the program must output one number each time round its loop");
+    }
+    match shift {
+        Some(shift) => Ok(shift),
+        None => unsupported("A must be shifted by adv each time round the loop"),
+    }
 }
 
-// Day17
-impl Day17 {
-    pub const fn new() -> Self {
-        Self { }
+impl Input {
+    // The smallest A that makes the program output target, or None if no A does.
+    fn smallest_a(&self, target: &[usize]) -> Result<Option<usize>, SolveError> {
+        let shift = match loop_shift(&self.program) {
+            Ok(shift) => shift,
+            Err(SolveError::Unsupported { .. }) => return self.smallest_a_symbolic(target),
+            Err(e) => return Err(e),
+        };
+
+        // Every pass outputs something.
+        if target.is_empty() {
+            return Ok(None);
+        }
+
+        self.extend_a(target, shift, target.len(), 0)
+    }
+
+    // The smallest A for any other program, searching all of A's bits symbolically.
+    fn smallest_a_symbolic(&self, target: &[usize]) -> Result<Option<usize>, SolveError> {
+        let starts = [Start::Unknown(usize::BITS as usize), Start::Known(self.b), Start::Known(self.c)];
+        let symbolic = Symbolic::run(&self.program, starts, target.len())?;
+
+        Ok(symbolic.smallest(target).map(|regs| regs.a))
+    }
+
+    // Find the smallest A whose top bits are high, which output target[done..],
+    // adding shift bits for each number still to match.
+    fn extend_a(&self, target: &[usize], shift: usize, done: usize, high: usize) -> Result<Option<usize>, SolveError> {
+        if done == 0 {
+            return Ok(Some(high));
+        }
+        if high.leading_zeros() < shift as u32 {
+            return Err(SolveError::unsupported(DAY, "A would be too large"));
+        }
+
+        for low in 0..1 << shift {
+            let a = (high << shift) | low;
+            let mut machine = Machine::new(self);
+            machine.a = a;
+            machine.run_to_halt()?;
+
+            if machine.output == target[done-1..] && let Some(a) = self.extend_a(target, shift, done-1, a)? {
+                return Ok(Some(a));
+            }
+        }
+
+        Ok(None)
     }
+}
 
-    // At a = 1 << (3*(N-1)), we see the first sequence with N digits
-    // We should see all the distinct values of the last digit at intervals
-    // of 1 << (3*(N-2)).
-    // We can work this into a search algorithm that only needs 8*(N digits) checks.
-    fn search(input: &Input) -> usize {
-        let prog_len = input.program.len();
+// The smallest value of register A that makes the program in text output target,
+// or None if there isn't one.  The program has to loop over A as described above.
+pub fn smallest_a(text: &str, target: &[usize]) -> Result<Option<usize>, SolveError> {
+    Input::read(text)?.smallest_a(target)
+}
+
+// Compiled programs, for brute force.  Rather than decoding each instruction as
+// it runs, as Machine does, the program is compiled once into basic blocks: a
+// closure for each instruction that only changes registers, ending at an out, a
+// jnz, a fault or the end of the program.  A run passes each output to a
+// callback, which can stop it early, so nothing is collected.
 
-        // println!("Searching for {:?}", input.program);
+type Regs = [usize; 3];  // A, B and C
 
-        let mut base = 0;
+// An instruction that only changes registers.
+type Step = Box<dyn Fn(&mut Regs) + Send + Sync>;
+
+// Where a combo operand's value comes from.
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Literal(usize),
+    Register(usize),
+}
 
-        // Loop breaks when full answer is found
+impl Source {
+    fn read(&self, regs: &Regs) -> usize {
+        match *self {
+            Source::Literal(n) => n,
+            Source::Register(r) => regs[r],
+        }
+    }
+}
+
+// How a basic block ends.
+enum Exit {
+    Out(Source, usize),     // Output a value, then go on at an address.
+    Jnz(usize, usize),      // Jump to the first address, or go on at the second.
+    Halt,
+    Fault(SolveError),
+}
+
+struct Block {
+    steps: Vec<Step>,
+    exit: Exit,
+    cycles: usize,          // Instructions run, counting the exit.
+}
+
+// How a compiled run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Run {
+    Halted,
+    Stopped,        // The output callback asked to stop.
+    CycleLimit,
+}
+
+pub struct Compiled {
+    blocks: Vec<Option<Block>>,     // By the address each starts at.
+}
+
+impl Compiled {
+    pub fn compile(program: &[usize]) -> Compiled {
+        let mut blocks: Vec<Option<Block>> = (0..program.len()).map(|_| None).collect();
+        let mut starts = vec![0];
+        while let Some(start) = starts.pop() {
+            if start < program.len() && blocks[start].is_none() {
+                let block = Compiled::block(program, start, &mut starts);
+                blocks[start] = Some(block);
+            }
+        }
+
+        Compiled { blocks }
+    }
+
+    // The block starting at addr, adding the addresses it can go on to to starts.
+    fn block(program: &[usize], mut addr: usize, starts: &mut Vec<usize>) -> Block {
+        let mut steps: Vec<Step> = Vec::new();
         loop {
-            // Run the program with a at our current base
-            let mut machine = Machine::new(&input);
-            // println!("base: {base}");
-            machine.a = base;
-            machine.run_to_halt();
-
-            // Figure out highest incorrect digit
-            let mut highest_err = None;
-            for i in (0..prog_len).rev() {
-                if machine.output.len() < i {
-                    highest_err = Some(i);
-                    break;
+            let cycles = steps.len() + 1;
+            let fault = |msg: String| Block { steps: Vec::new(), exit: Exit::Fault(SolveError::bad_input(DAY, &msg)), cycles: 0 };
+            if addr >= program.len() {
+                return Block { exit: Exit::Halt, cycles: steps.len(), steps };
+            }
+            let Some(&operand) = program.get(addr+1) else {
+                return Block { steps, ..fault(format!("missing operand at {}", addr+1)) };
+            };
+            let Some(op) = Op::decode(program[addr]) else {
+                return Block { steps, ..fault(format!("illegal opcode {} at {addr}", program[addr])) };
+            };
+            let source = match operand {
+                _ if op.operand() != Operand::Combo => Source::Literal(operand),
+                0..4 => Source::Literal(operand),
+                4..7 => Source::Register(operand-4),
+                _ => return Block { steps, ..fault(format!("illegal combo operand {operand} at {addr}")) },
+            };
+
+            let step: Step = match (op, source) {
+                (Op::Out, _) => {
+                    starts.push(addr+2);
+                    return Block { steps, exit: Exit::Out(source, addr+2), cycles };
+                }
+                (Op::Jnz, _) => {
+                    starts.extend([operand, addr+2]);
+                    return Block { steps, exit: Exit::Jnz(operand, addr+2), cycles };
                 }
-                if machine.output[i] != input.program[i] {
-                    highest_err = Some(i);
-                    break;                    
+                (Op::Adv | Op::Bdv | Op::Cdv, Source::Literal(by)) => {
+                    let to = [Op::Adv, Op::Bdv, Op::Cdv].iter().position(|o| *o == op).unwrap();
+                    Box::new(move |r| r[to] = r[0] >> by)
                 }
+                (Op::Adv | Op::Bdv | Op::Cdv, Source::Register(by)) => {
+                    let to = [Op::Adv, Op::Bdv, Op::Cdv].iter().position(|o| *o == op).unwrap();
+                    Box::new(move |r| r[to] = shr(r[0], r[by]))
+                }
+                (Op::Bxl, _) => Box::new(move |r| r[1] ^= operand),
+                (Op::Bst, Source::Literal(n)) => Box::new(move |r| r[1] = n),
+                (Op::Bst, Source::Register(from)) => Box::new(move |r| r[1] = r[from] & 7),
+                (Op::Bxc, _) => Box::new(|r| r[1] ^= r[2]),
+            };
+            steps.push(step);
+            addr += 2;
+        }
+    }
+
+    // Run from regs, passing each output to out, which returns false to stop the
+    // run.  Runs longer than about limit instructions are stopped too.
+    pub fn run<F>(&self, mut regs: Regs, limit: usize, mut out: F) -> Result<Run, SolveError>
+        where F: FnMut(usize) -> bool
+    {
+        let mut addr = 0;
+        let mut cycles = 0;
+        loop {
+            let Some(Some(block)) = self.blocks.get(addr) else {
+                return Ok(Run::Halted);
+            };
+            cycles += block.cycles;
+            if cycles > limit {
+                return Ok(Run::CycleLimit);
+            }
+
+            for step in &block.steps {
+                step(&mut regs);
+            }
+            addr = match block.exit {
+                Exit::Out(source, next) => {
+                    if !out(source.read(&regs) & 7) {
+                        return Ok(Run::Stopped);
+                    }
+                    next
+                }
+                Exit::Jnz(target, next) => if regs[0] != 0 { target } else { next },
+                Exit::Halt => return Ok(Run::Halted),
+                Exit::Fault(ref e) => return Err(e.clone()),
+            };
+        }
+    }
+
+    // Everything the program outputs, starting from regs.
+    pub fn output(&self, regs: Regs) -> Result<Vec<usize>, SolveError> {
+        let mut output = Vec::new();
+        match self.run(regs, DEFAULT_CYCLE_LIMIT, |n| { output.push(n); true })? {
+            Run::CycleLimit => Err(SolveError::unsupported(DAY, &format!("the program ran for more than {DEFAULT_CYCLE_LIMIT} cycles"))),
+            _ => Ok(output),
+        }
+    }
+
+    // Does the program output exactly target, starting from regs?  The run stops
+    // as soon as its output differs.
+    pub fn outputs(&self, regs: Regs, target: &[usize]) -> Result<bool, SolveError> {
+        let mut matched = 0;
+        let run = self.run(regs, DEFAULT_CYCLE_LIMIT, |n| {
+            let same = target.get(matched) == Some(&n);
+            matched += 1;
+            same
+        })?;
+
+        Ok(run == Run::Halted && matched == target.len())
+    }
+
+    // The smallest A in range that makes the program output target, trying As in
+    // chunks on jobs threads.  Once one is found, no chunk beyond it is started.
+    pub fn brute_force(&self, range: Range<usize>, b: usize, c: usize, target: &[usize], jobs: usize) -> Result<Option<usize>, SolveError> {
+        const CHUNK: usize = 1 << 16;
+
+        let next = AtomicUsize::new(range.start);
+        let best = AtomicUsize::new(usize::MAX);
+        let fault = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.max(1) {
+                scope.spawn(|| {
+                    loop {
+                        // Claim the next chunk.  The counter saturates rather than
+                        // wrapping, so a range ending near usize::MAX stays in range.
+                        let claim = next.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |start| {
+                            (start < range.end).then(|| start.saturating_add(CHUNK))
+                        });
+                        let Ok(start) = claim else { break };
+                        if start >= best.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        for a in start..range.end.min(start.saturating_add(CHUNK)) {
+                            match self.outputs([a, b, c], target) {
+                                Ok(false) => continue,
+                                Ok(true) => { best.fetch_min(a, Ordering::Relaxed); }
+                                Err(e) => { fault.lock().unwrap().get_or_insert(e); best.store(0, Ordering::Relaxed); }
+                            }
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(e) = fault.into_inner().unwrap() {
+            return Err(e);
+        }
+        let best = best.into_inner();
+
+        Ok((best != usize::MAX).then_some(best))
+    }
+}
+
+// The smallest A in range that makes the program in text output target, found
+// by trying every one on jobs threads.
+pub fn brute_force_a(text: &str, range: Range<usize>, target: &[usize], jobs: usize) -> Result<Option<usize>, SolveError> {
+    let input = Input::read(text)?;
+    Compiled::compile(&input.program).brute_force(range, input.b, input.c, target, jobs)
+}
+
+// Symbolic execution.  Registers that start unknown are words of bits, each bit a
+// formula over the unknown starting bits, built in a Circuit of logic gates.
+// Running the program on them gives its outputs as formulas.  A jnz whose A
+// might or might not be zero forks the run, so there's a Path for each way
+// through the program, with the conditions that put the run on it.  Then a
+// backtracking search over the unknown bits finds every start giving an output.
+
+// A bit of a symbolic word: the index of its gate in the circuit.
+pub type Bit = usize;
+
+const FALSE: Bit = 0;
+const TRUE: Bit = 1;
+
+// The machine's registers are 64 bits wide.
+const WORD_BITS: usize = 64;
+
+type Word = Vec<Bit>;  // Least significant bit first.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Gate {
+    Const(bool),
+    Var(usize),
+    Not(Bit),
+    And(Bit, Bit),
+    Or(Bit, Bit),
+    Xor(Bit, Bit),
+}
+
+// Gates, each only taking inputs from gates before it.  Equal gates are shared,
+// and gates with constant inputs are simplified away.
+struct Circuit {
+    gates: Vec<Gate>,
+    index: HashMap<Gate, Bit>,
+}
+
+impl Circuit {
+    fn new() -> Circuit {
+        let mut circuit = Circuit { gates: Vec::new(), index: HashMap::new() };
+        circuit.add(Gate::Const(false));
+        circuit.add(Gate::Const(true));
+
+        circuit
+    }
+
+    fn add(&mut self, gate: Gate) -> Bit {
+        if let Some(bit) = self.index.get(&gate) {
+            return *bit;
+        }
+        self.gates.push(gate);
+        self.index.insert(gate, self.gates.len()-1);
+
+        self.gates.len()-1
+    }
+
+    fn constant(value: bool) -> Bit {
+        if value { TRUE } else { FALSE }
+    }
+
+    fn not(&mut self, x: Bit) -> Bit {
+        match self.gates[x] {
+            Gate::Const(v) => Circuit::constant(!v),
+            Gate::Not(y) => y,
+            _ => self.add(Gate::Not(x)),
+        }
+    }
+
+    fn and(&mut self, x: Bit, y: Bit) -> Bit {
+        match (x, y) {
+            (FALSE, _) | (_, FALSE) => FALSE,
+            (TRUE, other) | (other, TRUE) => other,
+            _ if x == y => x,
+            _ => self.add(Gate::And(x.min(y), x.max(y))),
+        }
+    }
+
+    fn or(&mut self, x: Bit, y: Bit) -> Bit {
+        match (x, y) {
+            (TRUE, _) | (_, TRUE) => TRUE,
+            (FALSE, other) | (other, FALSE) => other,
+            _ if x == y => x,
+            _ => self.add(Gate::Or(x.min(y), x.max(y))),
+        }
+    }
+
+    fn xor(&mut self, x: Bit, y: Bit) -> Bit {
+        match (x, y) {
+            (FALSE, other) | (other, FALSE) => other,
+            (TRUE, other) | (other, TRUE) => self.not(other),
+            _ if x == y => FALSE,
+            _ => self.add(Gate::Xor(x.min(y), x.max(y))),
+        }
+    }
+
+    // if c then t else e
+    fn mux(&mut self, c: Bit, t: Bit, e: Bit) -> Bit {
+        match c {
+            TRUE => t,
+            FALSE => e,
+            _ if t == e => t,
+            _ => {
+                let not_c = self.not(c);
+                let (t, e) = (self.and(c, t), self.and(not_c, e));
+                self.or(t, e)
+            }
+        }
+    }
+
+    fn word(value: usize) -> Word {
+        (0..WORD_BITS).map(|i| Circuit::constant(value >> i & 1 == 1)).collect()
+    }
+
+    fn xor_words(&mut self, x: &Word, y: &Word) -> Word {
+        x.iter().zip(y).map(|(a, b)| self.xor(*a, *b)).collect()
+    }
+
+    // x shifted right by s, a stage per bit of s.  Shifting by 64 or more gives 0.
+    fn shr(&mut self, x: &Word, s: &Word) -> Word {
+        let mut x = x.clone();
+        for (j, sj) in s.iter().enumerate().filter(|(_, sj)| **sj != FALSE) {
+            let by = if j < 7 { 1 << j } else { WORD_BITS };
+            x = (0..WORD_BITS)
+                .map(|i| {
+                    let shifted = x.get(i+by).copied().unwrap_or(FALSE);
+                    self.mux(*sj, shifted, x[i])
+                })
+                .collect();
+        }
+
+        x
+    }
+
+    // Is any bit of x set?
+    fn any(&mut self, x: &Word) -> Bit {
+        x.iter().fold(FALSE, |acc, bit| self.or(acc, *bit))
+    }
+
+    // The value of every gate up to top, as far as it's known from the variables
+    // assigned so far.
+    fn eval(&self, vars: &[Option<bool>], top: Bit, values: &mut Vec<Option<bool>>) {
+        values.clear();
+        for gate in &self.gates[..=top] {
+            let value = match *gate {
+                Gate::Const(v) => Some(v),
+                Gate::Var(n) => vars[n],
+                Gate::Not(x) => values[x].map(|v| !v),
+                Gate::And(x, y) => match (values[x], values[y]) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+                Gate::Or(x, y) => match (values[x], values[y]) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                },
+                Gate::Xor(x, y) => match (values[x], values[y]) {
+                    (Some(a), Some(b)) => Some(a != b),
+                    _ => None,
+                },
+            };
+            values.push(value);
+        }
+    }
+}
+
+// How a register starts a symbolic run: with a known value, or unknown with up
+// to this many bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Start {
+    Known(usize),
+    Unknown(usize),
+}
+
+// One way through the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub outputs: Vec<[Bit; 3]>,         // Least significant bit first.
+    pub conditions: Vec<(Bit, bool)>,   // Each must have this value to take the path.
+}
+
+// Starting registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Registers {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
+
+// A backtracking search over the unknown bits, for one path.
+struct Search<'a> {
+    constraints: Vec<(Bit, bool)>,  // Each must have this value.
+    order: &'a [usize],             // The unknowns, in the order they're set.
+    vars: Vec<Option<bool>>,        // The value of each unknown set so far.
+    values: Vec<Option<bool>>,      // The circuit's values, from Circuit::eval.
+    first: bool,                    // Stop at the first start found.
+}
+
+// Runs that fork more than this are given up on.
+pub const MAX_PATHS: usize = 10_000;
+
+pub struct Symbolic {
+    circuit: Circuit,
+    starts: [Start; 3],
+    vars: Vec<(char, usize)>,  // The register and bit of each unknown.
+    pub paths: Vec<Path>,
+}
+
+// A symbolic run in progress.
+#[derive(Clone)]
+struct SymState {
+    ip: usize,
+    regs: [Word; 3],
+    cycles: usize,
+    path: Path,
+}
+
+impl Symbolic {
+    // Run program from starts (for A, B and C), following every path that outputs
+    // no more than max_outputs numbers.
+    pub fn run(program: &[usize], starts: [Start; 3], max_outputs: usize) -> Result<Symbolic, SolveError> {
+        let mut circuit = Circuit::new();
+        let mut vars = Vec::new();
+        let regs: Vec<Word> = starts.iter().zip(['A', 'B', 'C'])
+            .map(|(start, name)| match *start {
+                Start::Known(value) => Circuit::word(value),
+                Start::Unknown(bits) => (0..WORD_BITS)
+                    .map(|i| if i < bits {
+                        vars.push((name, i));
+                        circuit.add(Gate::Var(vars.len()-1))
+                    } else {
+                        FALSE
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let start = SymState {
+            ip: 0,
+            regs: [regs[0].clone(), regs[1].clone(), regs[2].clone()],
+            cycles: 0,
+            path: Path { outputs: Vec::new(), conditions: Vec::new() },
+        };
+        let mut symbolic = Symbolic { circuit, starts, vars, paths: Vec::new() };
+        let mut running = vec![start];
+        while let Some(state) = running.pop() {
+            if symbolic.paths.len() + running.len() > MAX_PATHS {
+                return Err(SolveError::unsupported(DAY, &format!("the program has more than {MAX_PATHS} paths")));
+            }
+            symbolic.follow(program, state, max_outputs, &mut running)?;
+        }
+
+        Ok(symbolic)
+    }
+
+    // Run state until it halts, outputs too much or forks, when the other branch
+    // is added to running.
+    fn follow(&mut self, program: &[usize], mut state: SymState, max_outputs: usize, running: &mut Vec<SymState>) -> Result<(), SolveError> {
+        let c = &mut self.circuit;
+        while state.ip < program.len() {
+            state.cycles += 1;
+            if state.cycles > DEFAULT_CYCLE_LIMIT {
+                return Err(SolveError::unsupported(DAY, &format!("a path ran for more than {DEFAULT_CYCLE_LIMIT} cycles")));
             }
-            match highest_err {
-                None => {
-                    // We found the full match at base
-                    // println!("Found {:?}", machine.output);
-                    break;
+
+            let (op, _) = instruction(program, state.ip)?;
+            let operand = program[state.ip+1];
+            let combo = match operand {
+                4..7 => state.regs[operand-4].clone(),
+                _ => Circuit::word(operand),
+            };
+            let [a, b, cr] = &state.regs;
+            state.ip += 2;
+
+            match op {
+                Op::Adv => state.regs[0] = c.shr(a, &combo),
+                Op::Bxl => state.regs[1] = c.xor_words(b, &Circuit::word(operand)),
+                Op::Bst => state.regs[1] = combo.iter().enumerate().map(|(i, bit)| if i < 3 { *bit } else { FALSE }).collect(),
+                Op::Jnz => {
+                    let nonzero = c.any(a);
+                    if nonzero == TRUE {
+                        state.ip = operand;
+                    }
+                    else if nonzero != FALSE {
+                        // Fork: the other state jumps.
+                        let mut jumped = state.clone();
+                        jumped.ip = operand;
+                        jumped.path.conditions.push((nonzero, true));
+                        running.push(jumped);
+                        state.path.conditions.push((nonzero, false));
+                    }
                 }
-                Some(place) => {
-                    let interval = 1 << 3*(place);
-                    // println!("mismatch at place {place} base: {base}+{interval} -> {}", base+interval);
-                    base += interval;
+                Op::Bxc => state.regs[1] = c.xor_words(b, cr),
+                Op::Out => {
+                    if state.path.outputs.len() == max_outputs {
+                        return Ok(());
+                    }
+                    state.path.outputs.push([combo[0], combo[1], combo[2]]);
                 }
+                Op::Bdv => state.regs[1] = c.shr(a, &combo),
+                Op::Cdv => state.regs[2] = c.shr(a, &combo),
             }
         }
 
-        base
+        self.paths.push(state.path);
+        Ok(())
+    }
+
+    // Every start that makes the program output target, in order.
+    pub fn solve(&self, target: &[usize]) -> Vec<Registers> {
+        let order: Vec<usize> = (0..self.vars.len()).collect();
+        let mut found = Vec::new();
+        for path in self.paths.iter().filter(|p| p.outputs.len() == target.len()) {
+            self.backtrack(&mut self.search(path, target, &order, false), 0, &mut found);
+        }
+        found.sort();
+
+        found
+    }
+
+    // The smallest start that makes the program output target: with the smallest A,
+    // then B, then C.  Setting each register's bits from the top, 0 before 1, the
+    // first start found on a path is its smallest.
+    pub fn smallest(&self, target: &[usize]) -> Option<Registers> {
+        let mut order: Vec<usize> = (0..self.vars.len()).collect();
+        order.sort_by_key(|n| (self.vars[*n].0, std::cmp::Reverse(self.vars[*n].1)));
+
+        let mut found = Vec::new();
+        for path in self.paths.iter().filter(|p| p.outputs.len() == target.len()) {
+            self.backtrack(&mut self.search(path, target, &order, true), 0, &mut found);
+        }
+
+        found.into_iter().min()
+    }
+
+    // A search of path for starts outputting target: the path's conditions, and
+    // each output bit matching the target's.
+    fn search<'a>(&self, path: &Path, target: &[usize], order: &'a [usize], first: bool) -> Search<'a> {
+        let mut constraints = path.conditions.clone();
+        for (bits, value) in path.outputs.iter().zip(target) {
+            for (i, bit) in bits.iter().enumerate() {
+                constraints.push((*bit, value >> i & 1 == 1));
+            }
+        }
+
+        Search { constraints, order, vars: vec![None; self.vars.len()], values: Vec::new(), first }
+    }
+
+    // Try both values of each unknown bit in turn, dropping any assignment that
+    // already breaks a constraint.  Returns true to stop the search.
+    fn backtrack(&self, search: &mut Search, next: usize, found: &mut Vec<Registers>) -> bool {
+        let top = search.constraints.iter().map(|(bit, _)| *bit).max().unwrap_or(TRUE);
+        self.circuit.eval(&search.vars, top, &mut search.values);
+        if search.constraints.iter().any(|(bit, want)| search.values[*bit] == Some(!want)) {
+            return false;
+        }
+
+        if next == search.order.len() {
+            found.push(self.registers(&search.vars));
+            return search.first;
+        }
+        let var = search.order[next];
+        for value in [false, true] {
+            search.vars[var] = Some(value);
+            if self.backtrack(search, next+1, found) {
+                return true;
+            }
+        }
+        search.vars[var] = None;
+
+        false
+    }
+
+    // The starting registers given values for the unknowns.
+    fn registers(&self, vars: &[Option<bool>]) -> Registers {
+        let mut regs = self.starts.map(|start| match start {
+            Start::Known(value) => value,
+            Start::Unknown(_) => 0,
+        });
+        for ((name, bit), value) in self.vars.iter().zip(vars) {
+            if *value == Some(true) {
+                regs["ABC".find(*name).unwrap()] |= 1 << bit;
+            }
+        }
+
+        Registers { a: regs[0], b: regs[1], c: regs[2] }
+    }
+
+    // A bit as a formula over the unknowns, such as "a3 ^ (a0 & !b1)".  Gates
+    // named in names are written as their names.
+    fn formula(&self, bit: Bit, names: &HashMap<Bit, String>, top: bool) -> String {
+        if let Some(name) = names.get(&bit) {
+            return name.clone();
+        }
+        let operands = |x: Bit, y: Bit, op: &str| {
+            let text = format!("{} {op} {}", self.formula(x, names, false), self.formula(y, names, false));
+            if top { text } else { format!("({text})") }
+        };
+
+        match self.gates_at(bit) {
+            Gate::Const(v) => String::from(if v { "1" } else { "0" }),
+            Gate::Var(n) => format!("{}{}", self.vars[n].0.to_ascii_lowercase(), self.vars[n].1),
+            Gate::Not(x) => format!("!{}", self.formula(x, names, false)),
+            Gate::And(x, y) => operands(x, y, "&"),
+            Gate::Or(x, y) => operands(x, y, "|"),
+            Gate::Xor(x, y) => operands(x, y, "^"),
+        }
+    }
+
+    fn gates_at(&self, bit: Bit) -> Gate {
+        self.circuit.gates[bit]
+    }
+
+    // A path written out: the conditions to take it, then each output's bits, most
+    // significant first.  Formulas used more than once are given names, t1, t2, ...
+    pub fn describe(&self, path: &Path) -> String {
+        let roots: Vec<Bit> = path.conditions.iter().map(|(bit, _)| *bit)
+            .chain(path.outputs.iter().flat_map(|bits| bits.iter().copied()))
+            .collect();
+
+        // Count the uses of each gate reachable from the roots.
+        let mut uses: HashMap<Bit, usize> = HashMap::new();
+        let mut stack = roots.clone();
+        while let Some(bit) = stack.pop() {
+            let count = uses.entry(bit).or_default();
+            *count += 1;
+            if *count == 1 {
+                match self.gates_at(bit) {
+                    Gate::Not(x) => stack.push(x),
+                    Gate::And(x, y) | Gate::Or(x, y) | Gate::Xor(x, y) => stack.extend([x, y]),
+                    _ => (),
+                }
+            }
+        }
+
+        let mut shared: Vec<Bit> = uses.iter()
+            .filter(|(bit, count)| **count > 1 && !matches!(self.gates_at(**bit), Gate::Const(_) | Gate::Var(_) | Gate::Not(_)))
+            .map(|(bit, _)| *bit)
+            .collect();
+        shared.sort();
+
+        let mut names = HashMap::new();
+        let mut text = String::new();
+        for (n, bit) in shared.iter().enumerate() {
+            text.push_str(&format!("t{} = {}\n", n+1, self.formula(*bit, &names, true)));
+            names.insert(*bit, format!("t{}", n+1));
+        }
+        for (bit, value) in &path.conditions {
+            let want = if *value { "A != 0" } else { "A == 0" };
+            text.push_str(&format!("jnz with {want}: {} = {}\n", self.formula(*bit, &names, true), *value as u8));
+        }
+        for (n, bits) in path.outputs.iter().enumerate() {
+            let bits: Vec<String> = bits.iter().rev().map(|bit| self.formula(*bit, &names, true)).collect();
+            text.push_str(&format!("output {n}: [{}]\n", bits.join(", ")));
+        }
+
+        text
+    }
+}
+
+// Every A below 2^bits that makes the program in text output target, in order,
+// with B and C as the input gives them.
+pub fn every_a(text: &str, target: &[usize], bits: usize) -> Result<Vec<usize>, SolveError> {
+    let input = Input::read(text)?;
+    let starts = [Start::Unknown(bits), Start::Known(input.b), Start::Known(input.c)];
+    let symbolic = Symbolic::run(&input.program, starts, target.len())?;
+
+    Ok(symbolic.solve(target).iter().map(|regs| regs.a).collect())
+}
+
+// The debugger: runs a program an instruction at a time, stopping at breakpoints,
+// on output or when a watched register changes, and records a trace of every
+// instruction run.  A cycle limit stops programs that never halt.  It's driven by
+// commands, as typed at `aoc2024 debug17 <file>`; see DEBUG_HELP.
+
+pub const DEFAULT_CYCLE_LIMIT: usize = 100_000;
+
+pub const DEBUG_HELP: &str = "\
+Commands:
+  s, step [n]          Run n instructions (default 1)
+  c, continue          Run until a breakpoint, output break, watch, halt or the cycle limit
+  b, break [addr]      Stop before running the instruction at addr, or list breakpoints
+  clear <addr|all>     Remove a breakpoint, or all of them
+  output [value|off]   Stop after any output, or only after value, or never
+  watch <a|b|c|off>    Stop when a register changes, or stop watching them
+  set <a|b|c> <value>  Change a register
+  limit <cycles>       Stop after this many cycles (default 100000)
+  r, regs              Show the registers and the output so far
+  l, list              List the program, marking breakpoints and the next instruction
+  reset                Start again from the input's registers
+  trace <file>         Write every instruction run so far to file, as CSV
+  q, quit              Leave the debugger
+";
+
+// One instruction run: the machine's state before it, and anything it output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStep {
+    pub cycle: usize,
+    pub ip: usize,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub output: Option<usize>,
+}
+
+// Why the debugger stopped running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stop {
+    Stepped,
+    Halted,
+    Breakpoint(usize),
+    Output(usize),
+    Watch(char, usize, usize),  // Register, old value, new value.
+    CycleLimit,
+    Fault(SolveError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputBreak {
+    Never,
+    Any,
+    Value(usize),
+}
+
+pub struct Debugger {
+    input: Input,
+    machine: Machine,
+    breakpoints: BTreeSet<usize>,
+    output_break: OutputBreak,
+    watches: Vec<char>,
+    limit: usize,
+    trace: Vec<TraceStep>,
+}
+
+impl Debugger {
+    pub fn new(text: &str) -> Result<Debugger, ParseError> {
+        let input = Input::read(text)?;
+        let machine = Machine::new(&input);
+
+        Ok(Debugger {
+            input,
+            machine,
+            breakpoints: BTreeSet::new(),
+            output_break: OutputBreak::Never,
+            watches: Vec::new(),
+            limit: DEFAULT_CYCLE_LIMIT,
+            trace: Vec::new(),
+        })
+    }
+
+    fn register(&self, name: char) -> usize {
+        match name {
+            'A' => self.machine.a,
+            'B' => self.machine.b,
+            _ => self.machine.c,
+        }
+    }
+
+    // Run up to count instructions, or until something stops the machine.  A
+    // breakpoint at the first instruction is passed over, so a run can continue
+    // from one.
+    pub fn run(&mut self, count: Option<usize>) -> Stop {
+        let mut ran = 0;
+        loop {
+            if self.machine.ip >= self.machine.program.len() {
+                return Stop::Halted;
+            }
+            if count.is_some_and(|n| ran >= n) {
+                return Stop::Stepped;
+            }
+            if ran > 0 && self.breakpoints.contains(&self.machine.ip) {
+                return Stop::Breakpoint(self.machine.ip);
+            }
+            if self.machine.cycles >= self.limit {
+                return Stop::CycleLimit;
+            }
+
+            let m = &self.machine;
+            let mut step = TraceStep { cycle: m.cycles, ip: m.ip, a: m.a, b: m.b, c: m.c, output: None };
+            let watched: Vec<usize> = self.watches.iter().map(|r| self.register(*r)).collect();
+            let outputs = m.output.len();
+
+            if let Err(e) = self.machine.step() {
+                // The machine stays at the fault, so it's only traced once.
+                if self.trace.last() != Some(&step) {
+                    self.trace.push(step);
+                }
+                return Stop::Fault(e);
+            }
+            ran += 1;
+            step.output = self.machine.output.get(outputs).copied();
+            self.trace.push(step);
+
+            if let Some(value) = step.output {
+                match self.output_break {
+                    OutputBreak::Any => return Stop::Output(value),
+                    OutputBreak::Value(v) if v == value => return Stop::Output(value),
+                    _ => (),
+                }
+            }
+            for (register, old) in self.watches.iter().zip(watched) {
+                let new = self.register(*register);
+                if new != old {
+                    return Stop::Watch(*register, old, new);
+                }
+            }
+        }
+    }
+
+    // The registers, the output so far and the next instruction.
+    pub fn state(&self) -> String {
+        let m = &self.machine;
+        let output: Vec<String> = m.output.iter().map(|v| v.to_string()).collect();
+        let next = if m.ip >= m.program.len() {
+            String::from("halted")
+        }
+        else {
+            listing_line(&m.program, m.ip).unwrap_or_else(|e| e.to_string())
+        };
+
+        format!("cycle {}  A={} B={} C={}  output [{}]\n  {next}", m.cycles, m.a, m.b, m.c, output.join(","))
+    }
+
+    fn describe(&self, stop: &Stop) -> String {
+        let why = match stop {
+            Stop::Stepped => String::new(),
+            Stop::Halted => String::new(),
+            Stop::Breakpoint(addr) => format!("breakpoint at {addr}\n"),
+            Stop::Output(value) => format!("output {value}\n"),
+            Stop::Watch(register, old, new) => format!("{register} changed from {old} to {new}\n"),
+            Stop::CycleLimit => format!("stopped at the cycle limit of {}: the program may never halt\n", self.limit),
+            Stop::Fault(e) => format!("{e}\n"),
+        };
+
+        format!("{why}{}", self.state())
+    }
+
+    // The trace as CSV, a line per instruction run.
+    pub fn trace_csv(&self) -> String {
+        let mut csv = String::from("cycle,ip,instruction,a,b,c,output\n");
+        for step in &self.trace {
+            let instruction = match instruction(&self.machine.program, step.ip) {
+                Ok((op, operand)) => format!("{} {operand}", op.mnemonic()),
+                Err(_) => self.machine.program[step.ip..].iter().take(2).map(|v| v.to_string()).collect::<Vec<_>>().join(" "),
+            };
+            let output = step.output.map(|v| v.to_string()).unwrap_or_default();
+            csv.push_str(&format!("{},{},{instruction},{},{},{},{output}\n", step.cycle, step.ip, step.a, step.b, step.c));
+        }
+
+        csv
+    }
+
+    fn listing(&self) -> String {
+        let program = &self.machine.program;
+        let mut listing = String::new();
+        for addr in (0..program.len()).step_by(2) {
+            let mark = if addr == self.machine.ip { '>' } else { ' ' };
+            let brk = if self.breakpoints.contains(&addr) { '*' } else { ' ' };
+            let line = listing_line(program, addr).unwrap_or_else(|e| format!("{addr:2}: {e}"));
+            listing.push_str(&format!("{mark}{brk}{line}\n"));
+        }
+
+        listing
+    }
+
+    // Carry out a command, giving what to show for it, or None to quit.
+    pub fn command(&mut self, line: &str) -> Result<Option<String>, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: Option<&&str>| -> Result<usize, String> {
+            let word = word.ok_or("missing number")?;
+            word.parse().map_err(|_| format!("'{word}' isn't a number"))
+        };
+        let register = |word: Option<&&str>| -> Result<char, String> {
+            match word.map(|w| w.to_ascii_uppercase()).as_deref() {
+                Some("A") => Ok('A'),
+                Some("B") => Ok('B'),
+                Some("C") => Ok('C'),
+                _ => Err(String::from("expected a register, a, b or c")),
+            }
+        };
+
+        let shown = match words.first().copied().unwrap_or("") {
+            "" => String::new(),
+            "h" | "help" => String::from(DEBUG_HELP),
+            "q" | "quit" => return Ok(None),
+            "s" | "step" => {
+                let count = if words.len() > 1 { number(words.get(1))? } else { 1 };
+                let stop = self.run(Some(count));
+                self.describe(&stop)
+            }
+            "c" | "continue" => {
+                let stop = self.run(None);
+                self.describe(&stop)
+            }
+            "b" | "break" if words.len() == 1 => {
+                let addrs: Vec<String> = self.breakpoints.iter().map(|a| a.to_string()).collect();
+                format!("breakpoints: {}", addrs.join(" "))
+            }
+            "b" | "break" => {
+                let addr = number(words.get(1))?;
+                if addr % 2 == 1 || addr >= self.machine.program.len() {
+                    return Err(format!("there's no instruction at {addr}"));
+                }
+                self.breakpoints.insert(addr);
+                format!("breakpoint at {addr}")
+            }
+            "clear" if words.get(1) == Some(&"all") => {
+                self.breakpoints.clear();
+                String::from("breakpoints cleared")
+            }
+            "clear" => {
+                let addr = number(words.get(1))?;
+                if !self.breakpoints.remove(&addr) {
+                    return Err(format!("there's no breakpoint at {addr}"));
+                }
+                format!("breakpoint at {addr} cleared")
+            }
+            "output" => {
+                self.output_break = match words.get(1) {
+                    None => OutputBreak::Any,
+                    Some(&"off") => OutputBreak::Never,
+                    word => OutputBreak::Value(number(word)?),
+                };
+                String::from(match self.output_break {
+                    OutputBreak::Never => "won't stop on output",
+                    OutputBreak::Any => "stopping after every output",
+                    OutputBreak::Value(_) => "stopping after that output",
+                })
+            }
+            "watch" if words.get(1) == Some(&"off") => {
+                self.watches.clear();
+                String::from("not watching any registers")
+            }
+            "watch" => {
+                let r = register(words.get(1))?;
+                if !self.watches.contains(&r) {
+                    self.watches.push(r);
+                }
+                format!("watching {r}")
+            }
+            "set" => {
+                let r = register(words.get(1))?;
+                let value = number(words.get(2))?;
+                match r {
+                    'A' => self.machine.a = value,
+                    'B' => self.machine.b = value,
+                    _ => self.machine.c = value,
+                }
+                self.state()
+            }
+            "limit" => {
+                self.limit = number(words.get(1))?;
+                format!("cycle limit {}", self.limit)
+            }
+            "r" | "regs" => self.state(),
+            "l" | "list" => self.listing(),
+            "reset" => {
+                self.machine = Machine::new(&self.input);
+                self.trace.clear();
+                self.state()
+            }
+            "trace" => {
+                let path = words.get(1).ok_or("missing file name")?;
+                fs::write(path, self.trace_csv()).map_err(|e| format!("couldn't write {path}: {e}"))?;
+                format!("wrote {} steps to {path}", self.trace.len())
+            }
+            other => return Err(format!("unknown command '{other}', try help")),
+        };
+
+        Ok(Some(shown))
+    }
+
+    // Read commands from input until it ends or says quit, writing what they show to out.
+    pub fn repl<R: BufRead, W: Write>(&mut self, input: R, mut out: W) -> io::Result<()> {
+        writeln!(out, "{}", self.state())?;
+        write!(out, "> ")?;
+        out.flush()?;
+        for line in input.lines() {
+            match self.command(&line?) {
+                Ok(None) => break,
+                Ok(Some(shown)) if shown.is_empty() => (),
+                Ok(Some(shown)) => writeln!(out, "{}", shown.trim_end())?,
+                Err(msg) => writeln!(out, "error: {msg}")?,
+            }
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+// Debug the program in the file at path, reading commands from stdin.
+pub fn debug(path: &str) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    let mut debugger = Debugger::new(&text)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    debugger.repl(io::stdin().lock(), io::stdout())
+}
+
+solver!(Day17, year: 2024, day: 17, title: "Chronospatial Computer", tags: ["vm", "reverse-engineering"]);
+
+pub struct Day17 {
+}
+
+// Day17
+impl Day17 {
+    pub const fn new() -> Self {
+        Self { }
+    }
+
+    // The smallest A that makes the program output itself.
+    fn search(input: &Input) -> Result<usize, SolveError> {
+        input.smallest_a(&input.program)?
+            .ok_or_else(|| SolveError::bad_input(DAY, "no value of A makes the program output itself"))
     }
 
 }
@@ -218,18 +1482,18 @@ impl Day17 {
 impl<'a> Day for Day17 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let mut machine = Machine::new(&input);
 
-        Answer::String(machine.run_to_halt())
+        Ok(Answer::String(machine.run_to_halt()?))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::search(&input))
+        Ok(Answer::Numeric(Self::search(&input)?))
     }
 }
 
@@ -237,9 +1501,14 @@ impl<'a> Day for Day17 {
 
 mod test {
 
-    use crate::day17::{Day17, Input, Machine};
+    use std::collections::HashMap;
+
+    use crate::day17::{Compiled, Day17, loop_shift, Debugger, Input, Machine, Registers, Run, Start, Stop, Symbolic, assemble, brute_force_a, disassemble, every_a, program_line, smallest_a};
     use crate::day::{Day, Answer};
-    use data_aoc2024::DAY17_INPUT;
+    use crate::params::Params;
+    use crate::error::{ParseError, SolveError};
+    #[cfg(feature = "embedded")]
+    use crate::inputs::embedded_input;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -253,7 +1522,7 @@ Program: 0,1,5,4,3,0
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.a, 729);
         assert_eq!(input.b, 0);
@@ -262,18 +1531,389 @@ Program: 0,1,5,4,3,0
         assert_eq!(input.program, vec![0,1,5,4,3,0]);
     }
 
+    #[test]
+    // Lines that aren't recognized, and non 3-bit program values, are reported.
+    fn test_read_errors() {
+        let result = Input::read("Register A: 729\nRegister X: 0\n");
+        assert_eq!(result.err(), Some(ParseError::new(17, 2, 1, "Register X: 0", "expected a register or program")));
+
+        let result = Input::read("Register A: 729\n\nProgram: 0,1,9,4\n");
+        assert_eq!(result.err(), Some(ParseError::new(17, 3, 14, "9", "not a 3-bit number")));
+    }
+
     #[test]
     // Read and confirm inputs
     fn test_machine() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         let mut machine = Machine::new(&input);
         let s = machine.run_to_halt();
-        assert_eq!(s, "4,6,3,5,6,3,5,2,1,0");
+        assert_eq!(s, Ok(String::from("4,6,3,5,6,3,5,2,1,0")));
+    }
+
+    #[test]
+    // Programs that break the machine's rules are reported, not run.
+    fn test_machine_errors() {
+        let d = Day17::new();
+
+        // Combo operand 7 is reserved.
+        let result = d.part1("Register A: 729\n\nProgram: 0,7\n", &Params::default());
+        assert_eq!(result, Err(SolveError::bad_input(17, "illegal combo operand 7 at 0")));
+
+        // Every opcode needs an operand.
+        let result = d.part1("Register A: 729\n\nProgram: 1,2,5\n", &Params::default());
+        assert_eq!(result, Err(SolveError::bad_input(17, "missing operand at 3")));
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        let listing = disassemble(&input.program).unwrap();
+        assert_eq!(listing.lines().collect::<Vec<_>>(), vec![
+            " 0: adv 1     ; A = A >> 1",
+            " 2: out A     ; output A & 7",
+            " 4: jnz 0     ; if A != 0 jump to 0",
+        ]);
+        assert_eq!(disassemble(&[0, 7]), Err(SolveError::bad_input(17, "illegal combo operand 7 at 0")));
+        assert_eq!(disassemble(&[1, 2, 5]), Err(SolveError::bad_input(17, "missing operand at 3")));
+    }
+
+    #[test]
+    fn test_assemble() {
+        // Listings round trip, whatever the program does.
+        let program = vec![2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 0, 3, 5, 5, 3, 0];
+        assert_eq!(assemble(&disassemble(&program).unwrap()), Ok(program.clone()));
+        assert_eq!(program_line(&program), "Program: 2,4,1,1,7,5,1,5,4,0,0,3,5,5,3,0");
+
+        // Written by hand.
+        let text = "\
+; divide A by 8 until it's zero, printing it each time
+loop: adv 3
+      OUT a
+      bxc
+      jnz 0
+";
+        assert_eq!(assemble(text).err(), Some(ParseError::new(17, 2, 1, "loop", "invalid number")));
+        assert_eq!(assemble(&text.replace("loop", "   0")), Ok(vec![0, 3, 5, 4, 4, 0, 3, 0]));
+
+        assert_eq!(assemble("jmp 0").err(), Some(ParseError::new(17, 1, 1, "jmp", "unknown instruction")));
+        assert_eq!(assemble("adv").err(), Some(ParseError::new(17, 1, 1, "adv", "missing operand")));
+        assert_eq!(assemble("adv D").err(), Some(ParseError::new(17, 1, 5, "D", "expected a combo operand, 0 to 3, A, B or C")));
+        assert_eq!(assemble("bxl 8").err(), Some(ParseError::new(17, 1, 5, "8", "not a 3-bit number")));
+        assert_eq!(assemble("bxl 1 2").err(), Some(ParseError::new(17, 1, 7, "2", "unexpected text after the operand")));
+    }
+
+    #[test]
+    fn test_debugger() {
+        let mut debugger = Debugger::new(EXAMPLE1).unwrap();
+
+        // Breakpoints are passed over when a run starts at one.
+        debugger.command("break 4").unwrap();
+        assert_eq!(debugger.run(None), Stop::Breakpoint(4));
+        assert_eq!(debugger.run(None), Stop::Breakpoint(4));
+        assert_eq!(debugger.machine.output, vec![4, 6]);
+        debugger.command("clear 4").unwrap();
+
+        debugger.command("output 5").unwrap();
+        assert_eq!(debugger.run(None), Stop::Output(5));
+        debugger.command("output off").unwrap();
+
+        debugger.command("watch a").unwrap();
+        assert_eq!(debugger.run(Some(10)), Stop::Watch('A', 45, 22));
+        debugger.command("watch off").unwrap();
+
+        assert_eq!(debugger.run(Some(2)), Stop::Stepped);
+        assert_eq!(debugger.run(None), Stop::Halted);
+        assert_eq!(debugger.machine.output, vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+        assert_eq!(debugger.trace.len(), 30);
+
+        let csv = debugger.trace_csv();
+        assert_eq!(csv.lines().nth(2), Some("1,2,out A,364,0,0,4"));
+
+        assert_eq!(debugger.command("break 3"), Err(String::from("there's no instruction at 3")));
+        assert_eq!(debugger.command("set d 1"), Err(String::from("expected a register, a, b or c")));
+        assert_eq!(debugger.command("quit"), Ok(None));
+    }
+
+    #[test]
+    fn test_cycle_limit() {
+        // A is never changed, so the program loops forever.
+        let mut debugger = Debugger::new("Register A: 1\n\nProgram: 1,1,3,0\n").unwrap();
+        debugger.command("limit 100").unwrap();
+
+        assert_eq!(debugger.run(None), Stop::CycleLimit);
+        assert_eq!(debugger.machine.cycles, 100);
+
+        // Programs the machine rejects stop it.
+        let mut debugger = Debugger::new("Register A: 1\n\nProgram: 2,7\n").unwrap();
+        assert_eq!(debugger.run(None), Stop::Fault(SolveError::bad_input(17, "illegal combo operand 7 at 0")));
+
+        // A fault leaves the machine at the bad instruction, so it stops there again.
+        let mut debugger = Debugger::new("Register A: 1\n\nProgram: 2,7,5,4\n").unwrap();
+        let fault = Stop::Fault(SolveError::bad_input(17, "illegal combo operand 7 at 0"));
+        assert_eq!(debugger.run(None), fault);
+        assert_eq!(debugger.command("c"), debugger.command("c"));
+        assert_eq!(debugger.run(None), fault);
+        assert_eq!((debugger.machine.ip, debugger.machine.cycles), (0, 0));
+        assert!(debugger.machine.output.is_empty());
+        assert_eq!(debugger.trace_csv(), "cycle,ip,instruction,a,b,c,output\n0,0,2 7,1,0,0,\n");
+
+        // Shifting by a register's whole width or more leaves 0.
+        let mut debugger = Debugger::new("Register A: 8\n\nProgram: 0,4,5,4,3,0\n").unwrap();
+        debugger.command("set a 100").unwrap();
+        assert_eq!(debugger.run(None), Stop::Halted);
+        assert_eq!(debugger.machine.output, vec![0]);
+
+        // bdv and cdv too, agreeing with compiled programs.
+        let mut debugger = Debugger::new("Register A: 100\n\nProgram: 6,5,7,6,5,5,5,6\n").unwrap();
+        debugger.command("set b 64").unwrap();
+        debugger.command("set c 70").unwrap();
+        assert_eq!(debugger.run(None), Stop::Halted);
+        assert_eq!(debugger.machine.output, vec![0, 0]);
+        assert_eq!(Compiled::compile(&[6,5,7,6,5,5,5,6]).output([100, 64, 70]), Ok(vec![0, 0]));
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut debugger = Debugger::new(EXAMPLE1).unwrap();
+        let mut out = Vec::new();
+        debugger.repl("set a 8\nc\nfoo\nq\nc\n".as_bytes(), &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("output [4,2,1,0]"));
+        assert!(out.contains("error: unknown command 'foo'"));
+        assert!(out.ends_with("> "));
+        assert_eq!(debugger.machine.cycles, 12);
+    }
+
+    #[test]
+    fn test_smallest_a() {
+        // The example from part 2, which outputs itself.
+        let example = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n";
+        assert_eq!(Day17::search(&Input::read(example).unwrap()), Ok(117440));
+
+        // Other targets.  The program outputs A after shifting it, so the last
+        // number it outputs is always 0.
+        assert_eq!(smallest_a(example, &[0]), Ok(Some(0)));
+        assert_eq!(smallest_a(example, &[5, 0]), Ok(Some(0o50)));
+        assert_eq!(smallest_a(example, &[4]), Ok(None));
+        assert_eq!(smallest_a(example, &[0, 0]), Ok(None));
+        assert_eq!(smallest_a(example, &[]), Ok(None));
+    }
+
+    #[test]
+    fn test_smallest_a_exhaustive() {
+        // A program shaped like the real inputs.  Every output up to four numbers
+        // long comes from an A below 8^4, so the first such A is the smallest.
+        let text = "Register A: 0\n\nProgram: 2,4,1,1,7,5,1,5,4,0,0,3,5,5,3,0\n";
+        let input = Input::read(text).unwrap();
+
+        let mut first: HashMap<Vec<usize>, usize> = HashMap::new();
+        for a in 0..8*8*8*8 {
+            let mut machine = Machine::new(&input);
+            machine.a = a;
+            machine.run_to_halt().unwrap();
+            first.entry(machine.output).or_insert(a);
+        }
+        for (output, a) in first.iter() {
+            assert_eq!(input.smallest_a(output), Ok(Some(*a)));
+        }
+
+        // Some outputs can't be made.
+        let missing = (0..8*8).map(|n| vec![n / 8, n % 8]).find(|o| !first.contains_key(o)).unwrap();
+        assert_eq!(input.smallest_a(&missing), Ok(None));
+    }
+
+    #[test]
+    fn test_smallest_a_any_shape() {
+        let programs = [
+            ("5,4,3,0", "A must be shifted by adv each time round the loop"),
+            ("0,3,0,3,5,4,3,0", "A must be shifted just once for each output"),
+            ("0,4,5,4,3,0", "A must be shifted by 1, 2 or 3 bits"),
+            ("1,1,5,5,0,3,3,0", "B is read before it's set, so it carries from one output to the next"),
+            ("0,3,3,0,5,4", "the only jump must be the last instruction"),
+            ("0,3,5,4", "the program must end with jnz 0"),
+            ("0,3,5,4,5,4,3,0", "the program must output one number each time round its loop"),
+            ("2,4,1,5,7,5,5,5,0,3,5,6,3,0", "the program must output one number each time round its loop"),
+        ];
+        for (program, msg) in programs {
+            // Programs not shaped for building A up a few bits at a time...
+            let program: Vec<usize> = program.split(',').map(|n| n.parse().unwrap()).collect();
+            assert_eq!(loop_shift(&program), Err(SolveError::unsupported(17, &format!("can't solve for A, {msg}"))));
+
+            // ... are solved symbolically, finding the same smallest A as trying each
+            // in turn.  (Some As make the program run forever.)
+            let text = format!("Register A: 0\n\n{}\n", program_line(&program));
+            let compiled = Compiled::compile(&program);
+            let mut first: HashMap<Vec<usize>, usize> = HashMap::new();
+            for a in 0..1 << 12 {
+                let mut output = Vec::new();
+                if compiled.run([a, 0, 0], 1000, |n| { output.push(n); true }) == Ok(Run::Halted) {
+                    first.entry(output).or_insert(a);
+                }
+            }
+            for (output, a) in first.iter().filter(|(o, _)| o.len() <= 8).take(40) {
+                assert_eq!(smallest_a(&text, output), Ok(Some(*a)), "{text} {output:?}");
+            }
+        }
+
+        // No A at all.
+        assert_eq!(smallest_a("Program: 5,4,3,0\n", &[3]), Ok(None));
+        assert_eq!(smallest_a("Program: 0,3,5,4\n", &[1, 2]), Ok(None));
+        assert_eq!(smallest_a("Program: 0,3,5,4,5,4,3,0\n", &[1, 2, 3]), Ok(None));
+    }
+
+    #[test]
+    fn test_compiled() {
+        // Compiled programs output the same as the machine.
+        let programs = [EXAMPLE1, "Register A: 2024\n\nProgram: 0,3,5,4,3,0\n", "Register A: 0\n\nProgram: 2,4,1,1,7,5,1,5,4,0,0,3,5,5,3,0\n"];
+        for text in programs {
+            let input = Input::read(text).unwrap();
+            let compiled = Compiled::compile(&input.program);
+            for a in (0..100_000).step_by(97) {
+                let mut machine = Machine::new(&input);
+                machine.a = a;
+                machine.run_to_halt().unwrap();
+                assert_eq!(compiled.output([a, input.b, input.c]), Ok(machine.output.clone()));
+                assert_eq!(compiled.outputs([a, input.b, input.c], &machine.output), Ok(true));
+            }
+        }
+
+        // Runs stop early, at the first difference, or at the cycle limit.
+        let compiled = Compiled::compile(&[0,1,5,4,3,0]);
+        let mut output = Vec::new();
+        assert_eq!(compiled.run([0o1234, 0, 0], 100, |n| { output.push(n); n != 3 }), Ok(Run::Stopped));
+        assert_eq!(output, vec![6, 7, 3]);
+        assert_eq!(compiled.outputs([0o1234, 0, 0], &[6, 7, 4]), Ok(false));
+        assert_eq!(compiled.run([0o1234, 0, 0], 100, |_| true), Ok(Run::Halted));
+        assert_eq!(compiled.run([0o1234, 0, 0], 10, |_| true), Ok(Run::CycleLimit));
+        assert!(matches!(Compiled::compile(&[3,0]).output([1, 0, 0]), Err(SolveError::Unsupported { .. })));
+
+        // Faults are the machine's, raised when they're reached.
+        for (program, msg) in [(vec![5,4,0,7], "illegal combo operand 7 at 2"), (vec![8,0], "illegal opcode 8 at 0"), (vec![1,2,5], "missing operand at 3")] {
+            assert_eq!(Compiled::compile(&program).output([0, 0, 0]), Err(SolveError::bad_input(17, msg)));
+        }
+        assert_eq!(Compiled::compile(&[5,4,3,6,0,7]).output([1, 0, 0]), Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let example = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n";
+        for jobs in [1, 4] {
+            assert_eq!(brute_force_a(example, 0..1 << 18, &[0,3,5,4,3,0], jobs), Ok(Some(117440)));
+            assert_eq!(brute_force_a(example, 117441..1 << 18, &[0,3,5,4,3,0], jobs), Ok(Some(117441)));
+            assert_eq!(brute_force_a(example, 0..1 << 12, &[4], jobs), Ok(None));
+        }
+
+        // The smallest A agrees with the solver for part 2.
+        let text = "Register A: 0\n\nProgram: 2,4,1,1,7,5,1,5,4,0,0,3,5,5,3,0\n";
+        let compiled = Compiled::compile(&Input::read(text).unwrap().program);
+        for a in [5, 0o1234, 0o4567123] {
+            let target = compiled.output([a, 0, 0]).unwrap();
+            assert_eq!(brute_force_a(text, 0..1 << 24, &target, 3), smallest_a(text, &target));
+        }
+
+        // Ranges reaching the top of usize don't wrap round to small As.
+        let shift = "Program: 0,3,5,4\n";
+        let top = usize::MAX - 200_000..usize::MAX;
+        let expected = top.clone().find(|a| (a >> 3) % 8 == 0);
+        for jobs in [1, 4] {
+            assert_eq!(brute_force_a(shift, top.clone(), &[0], jobs), Ok(expected));
+            assert_eq!(brute_force_a(shift, usize::MAX - 10..usize::MAX, &[7], jobs), Ok(Some(usize::MAX - 7)));
+            assert_eq!(brute_force_a(shift, usize::MAX - 7..usize::MAX, &[0], jobs), Ok(None));
+        }
+
+        assert_eq!(brute_force_a("Program: 0,7\n", 0..100, &[0], 2), Err(SolveError::bad_input(17, "illegal combo operand 7 at 0")));
+    }
+
+    #[test]
+    fn test_symbolic() {
+        // B = A & 7 ^ 1, out B
+        let symbolic = Symbolic::run(&[2,4,1,1,5,5], [Start::Unknown(3), Start::Known(0), Start::Known(0)], 10).unwrap();
+        assert_eq!(symbolic.paths.len(), 1);
+        assert_eq!(symbolic.describe(&symbolic.paths[0]), "output 0: [a2, a1, !a0]\n");
+
+        // The part 2 example forks at its jnz: one path halts after one output, the
+        // other goes round again, when A is known to be 0.
+        let symbolic = Symbolic::run(&[0,3,5,4,3,0], [Start::Unknown(6), Start::Known(0), Start::Known(0)], 10).unwrap();
+        let paths: Vec<String> = symbolic.paths.iter().map(|p| symbolic.describe(p)).collect();
+        assert_eq!(paths, vec![
+            "jnz with A == 0: a5 | (a3 | a4) = 0\noutput 0: [a5, a4, a3]\n",
+            "jnz with A != 0: a5 | (a3 | a4) = 1\noutput 0: [a5, a4, a3]\noutput 1: [0, 0, 0]\n",
+        ]);
+
+        // Shared formulas are named.
+        let symbolic = Symbolic::run(&[4,0,5,5,4,0,5,5], [Start::Known(0), Start::Unknown(1), Start::Unknown(1)], 10).unwrap();
+        assert_eq!(symbolic.describe(&symbolic.paths[0]), "t1 = b0 ^ c0\noutput 0: [0, 0, t1]\noutput 1: [0, 0, c0 ^ t1]\n");
+    }
+
+    #[test]
+    fn test_symbolic_solve() {
+        // The example from part 2 ignores the low 3 bits of A.
+        let example = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n";
+        assert_eq!(every_a(example, &[0,3,5,4,3,0], 18), Ok((117440..117448).collect()));
+        assert_eq!(every_a(example, &[0,3,5,4,3,0], 15), Ok(vec![]));
+        assert_eq!(every_a(example, &[4], 18), Ok(vec![]));
+
+        // Unknown B and C.  B = B ^ 3, B = B ^ C, out B
+        let symbolic = Symbolic::run(&[1,3,4,0,5,5], [Start::Known(0), Start::Unknown(3), Start::Unknown(1)], 1).unwrap();
+        assert_eq!(symbolic.solve(&[5]), vec![Registers { a: 0, b: 6, c: 0 }, Registers { a: 0, b: 7, c: 1 }]);
+        assert_eq!(symbolic.solve(&[5, 5]), vec![]);
+        assert_eq!(symbolic.smallest(&[5]), Some(Registers { a: 0, b: 6, c: 0 }));
+        assert_eq!(symbolic.smallest(&[5, 5]), None);
+
+        // Faults are raised as they're reached, as the machine does, even after a
+        // jump to an odd address.
+        let faults = [
+            (vec![3,1,5,4], Start::Unknown(2), "missing operand at 4"),
+            (vec![3,3,5,0,7], Start::Known(1), "illegal combo operand 7 at 3"),
+            (vec![7], Start::Unknown(1), "missing operand at 1"),
+        ];
+        for (program, a, msg) in faults {
+            assert_eq!(Symbolic::run(&program, [a, Start::Known(0), Start::Known(0)], 4).err(), Some(SolveError::bad_input(17, msg)));
+        }
+        assert_eq!(Symbolic::run(&[3,3,5,4,0,7], [Start::Unknown(2), Start::Known(0), Start::Known(0)], 4).err(),
+            Some(SolveError::bad_input(17, "illegal combo operand 7 at 4")));
+        let symbolic = Symbolic::run(&[3,4,0,7,5,4], [Start::Known(1), Start::Known(0), Start::Known(0)], 4).unwrap();
+        assert_eq!(symbolic.solve(&[1]), vec![Registers { a: 1, b: 0, c: 0 }]);
+
+        // Programs that run forever.
+        assert!(matches!(Symbolic::run(&[3,0], [Start::Unknown(1), Start::Known(0), Start::Known(0)], 1), Err(SolveError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn test_symbolic_exhaustive() {
+        // A program shaped like the real inputs.  Symbolic runs find the same As as
+        // running the machine on every A of up to 12 bits.
+        let text = "Register A: 0\n\nProgram: 2,4,1,1,7,5,1,5,4,0,0,3,5,5,3,0\n";
+        let input = Input::read(text).unwrap();
+
+        let mut every: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+        for a in 0..1 << 12 {
+            let mut machine = Machine::new(&input);
+            machine.a = a;
+            machine.run_to_halt().unwrap();
+            every.entry(machine.output).or_default().push(a);
+        }
+        for (output, a) in every.iter().step_by(17) {
+            assert_eq!(every_a(text, output, 12).as_ref(), Ok(a));
+        }
+
+        // Making the program output itself takes 48 bits, and the smallest A agrees
+        // with the solver for part 2.
+        let every = every_a(text, &input.program, 48).unwrap();
+        assert_eq!(every.len(), 6);
+        assert_eq!(smallest_a(text, &input.program), Ok(every.first().copied()));
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_search() {
+        let Some(text) = embedded_input(17) else {
+            println!("Skipping day 17: no embedded input");
+            return;
+        };
         // length of output as a function of a:
         // 0-7 : 1
         // 8-64 : 2
@@ -283,8 +1923,8 @@ Program: 0,1,5,4,3,0
         // Try 8 numbers, separated by 8^(N-1), 
         // Then try numbers separated by 8^(N-2) to get next digit, etc.
 
-        let input = Input::read(DAY17_INPUT);
-        assert_eq!(Day17::search(&input), 164540892147389);
+        let input = Input::read(text).unwrap();
+        assert_eq!(Day17::search(&input), Ok(164540892147389));
     }
 
     #[test]
@@ -292,15 +1932,20 @@ Program: 0,1,5,4,3,0
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day17::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::String("4,6,3,5,6,3,5,2,1,0".to_string()));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::String("4,6,3,5,6,3,5,2,1,0".to_string())));
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
+        let Some(text) = embedded_input(17) else {
+            println!("Skipping day 17: no embedded input");
+            return;
+        };
         // Based on the example in part 2.
         let d = Day17::new();
-        assert_eq!(d.part2(DAY17_INPUT), Answer::Numeric(164540892147389));
+        assert_eq!(d.part2(text, &Params::default()), Ok(Answer::Numeric(164540892147389)));
     }
     
 }
\ No newline at end of file
@@ -1,16 +1,13 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::{Param, Params, Value};
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
+use crate::parse;
+use crate::search;
+use crate::timing;
+use crate::registry::solver;
 
-const START_TIME: usize = 1024;
-const PROBLEM_SIZE: usize = 71;
-
-lazy_static! {
-    static ref LINE_RE: Regex = Regex::new("(\\d+),(\\d+)").unwrap();
-}
+const DAY: usize = 18;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -20,108 +17,74 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str, size: usize) -> Input
+    fn read(text: &str, size: usize) -> Result<Input, ParseError>
     {
         let mut coords = Vec::new();
 
-        for line in text.lines() {
-            if let Some(caps) = LINE_RE.captures(line) {
-                let x = caps[1].parse().unwrap();
-                let y = caps[2].parse().unwrap();
-                coords.push( (x, y) );
+        for line in parse::lines(DAY, text) {
+            let [x, y] = line.ints_n::<usize, 2>("expected a coordinate like 5,4")?;
+            if (x >= size) | (y >= size) {
+                return Err(line.error("coordinate is outside the memory space"));
             }
+            coords.push( (x, y) );
         }
 
-        Input { size, coords }
+        Ok(Input { size, coords })
     }
 }
 
+solver!(Day18, year: 2024, day: 18, title: "RAM Run", tags: ["grid", "bfs"]);
+
 pub struct Day18 {
-    start_t: usize,
-    prob_size: usize,
 }
 
 // Day18
 impl Day18 {
     pub const fn new() -> Self {
-        Self { start_t: START_TIME, prob_size: PROBLEM_SIZE }
+        Self { }
     }
 
     fn solve(input: &Input, t: usize) -> Option<usize> {
 
         // Generate the set of blocked cells at time t
-        let mut blocked: HashSet<(usize, usize)> = HashSet::new();
-        let mut n = 0;
-        for block in input.coords.iter() {
-            blocked.insert(*block);
-            n += 1;
-            if n == t { break; }
+        let mut blocked = Grid::new(input.size, input.size, false);
+        for block in input.coords.iter().take(t) {
+            blocked[*block] = true;
         }
 
-        // Set out to explore the map, starting at (0, 0) at t=1
-        // Record the time, in steps, to reach each cell.
-        // (quit when end is reached.)
-        let mut frontier: VecDeque<(usize, usize)> = VecDeque::new();
-        let mut distance_to: HashMap<(usize, usize), usize> = HashMap::new();
-
-        distance_to.insert((0, 0), 0);
-        frontier.push_back((0, 0));
-
-        'outer: while let Some((x, y)) = frontier.pop_front() {
-            // println!("Exploring from: {}, {}", x, y);
-            // explore from x, y
-            let t = *distance_to.get(&(x, y)).unwrap();
-
-            // Assemble neighbors
-            let mut neighbors = Vec::new();
-            if y > 0            { neighbors.push( (x, y-1) ); }  // North neighbor
-            if y < input.size-1 { neighbors.push( (x, y+1) ); }  // South neighbor
-            if x > 0            { neighbors.push( (x-1, y) ); }  // West neighbor
-            if x < input.size-1 { neighbors.push( (x+1, y) ); }  // East neighbor
-
-            for next in &neighbors {
-                // println!(" Trying neighbor: {}, {}", next.0, next.1);
-                if !blocked.contains(next) {
-                    if !distance_to.contains_key(next) {
-                        distance_to.insert(*next, t+1);
-                        frontier.push_back(*next);
-                        // println!("    Reached {},{} in {} steps", next.0, next.1, t+1);
-
-                        // Did we reach the end?
-                        if *next == (input.size-1, input.size-1) {
-                            break 'outer;
-                        }
-                    }
-                    else {
-                        // println!("    Been there.")
-                    }
-                }
-                else {
-                    // println!("    Blocked.");
-                }
-            }
-        }
+        // Explore the map from (0, 0) for the shortest way to the far corner,
+        // counting the steps.
+        let blocked = &blocked;
+        let end = (input.size-1, input.size-1);
+        let open_neighbors = |pos: &(usize, usize)| blocked.neighbors4(*pos).filter(|next| !blocked[*next]);
 
-        distance_to.get( &(input.size-1, input.size-1) ).copied()
+        search::bfs_path((0, 0), open_neighbors, |pos| *pos == end)
+            .map(|path| path.len()-1)
     }
 
+    // The first byte to cut off the path, starting the search at start_t bytes,
+    // or None if the path is still open once every byte has fallen.
     fn find_cutoff(input: &Input, start_t: usize) -> Option<(usize, usize)> {
-        let mut low: usize = start_t;
-        let mut high: usize = start_t;
+        let fallen = input.coords.len();
+        if Self::solve(input, fallen).is_some() {
+            return None;
+        }
+
+        // With no bytes fallen there's a path, with all of them there isn't.
+        let mut low: usize = 0;
+        let mut high: usize = start_t.clamp(1, fallen);
 
         // grow high until it's high enough to cut off the path
-        let mut soln = Self::solve(input, high);
-        while let Some(_dist) = soln {
-            high *= 2;
-            soln = Self::solve(input, high);
+        while Self::solve(input, high).is_some() {
+            low = high;
+            high = (high*2).min(fallen);
         }
 
         // now binary search between low and high
 
         while high-low > 1 {
             let mid = (low+high)/2;
-            soln = Self::solve(input, mid);
-            match soln {
+            match Self::solve(input, mid) {
                 Some(_dist) => {
                     // Solved, raise low to mid
                     low = mid;
@@ -134,40 +97,47 @@ impl Day18 {
         }
 
         // high is the first setting where it's blocked.
-        let cutoff = Some(input.coords[high-1]);
-
-        cutoff
+        Some(input.coords[high-1])
     }
 }
 
 impl<'a> Day for Day18 {
 
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param { name: "size", default: Value::Unsigned(71), help: "width and height of the memory space" },
+            Param { name: "t", default: Value::Unsigned(1024), help: "bytes fallen before the path is found" },
+        ]
+    }
+
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text, self.prob_size);
+    fn part1(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let size = params.positive(DAY, "size")?;
+        let input = timing::parse(|| Input::read(text, size))?;
+        let t = params.usize("t");
+        if t > input.coords.len() {
+            return Err(SolveError::bad_input(DAY, &format!("only {} bytes fall, fewer than t = {t}", input.coords.len())));
+        }
 
-        match Self::solve(&input, self.start_t) {
+        match Self::solve(&input, t) {
             Some(n) => {
-                Answer::Numeric(n)
+                Ok(Answer::Numeric(n))
             }
             None => {
-                Answer::None
+                Ok(Answer::None)
             }
         }
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text, self.prob_size);
+    fn part2(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let size = params.positive(DAY, "size")?;
+        let input = timing::parse(|| Input::read(text, size))?;
 
-        let cutoff = Day18::find_cutoff(&input, self.start_t);
+        let cutoff = Day18::find_cutoff(&input, params.usize("t"));
 
         match cutoff {
-            Some((x, y)) => {
-                let mut out = String::new();
-                out.push_str(&format!("{},{}", x, y).to_string());
-                Answer::String(out)
-            }
-            None => Answer::None
+            Some((x, y)) => Ok(Answer::coord(x, y)),
+            None => Ok(Answer::None)
         }
     }
 }
@@ -178,6 +148,8 @@ mod test {
 
     use crate::day18::{Day18, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::error::SolveError;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -212,7 +184,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE);
+        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE).unwrap();
 
         assert_eq!(input.size, EXAMPLE1_SIZE);
         assert_eq!(input.coords.len(), 25);
@@ -220,7 +192,7 @@ mod test {
 
     #[test]
     fn test_steps() {
-        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE);
+        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE).unwrap();
 
         let steps = Day18::solve(&input, 12);
         assert_eq!(steps, Some(22));
@@ -228,10 +200,21 @@ mod test {
 
     #[test]
     fn test_cutoff() {
-        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE);
+        let input = Input::read(EXAMPLE1, EXAMPLE1_SIZE).unwrap();
 
         let cutoff = Day18::find_cutoff(&input, 12);
         assert_eq!(cutoff, Some((6,1)));
+
+        // Any starting guess finds the same byte.
+        for t in [0, 1, 20, 25, 100] {
+            assert_eq!(Day18::find_cutoff(&input, t), Some((6,1)));
+        }
+
+        // The first 20 bytes don't cut the path off.
+        let input = Input { size: EXAMPLE1_SIZE, coords: input.coords[..20].to_vec() };
+        assert_eq!(Day18::find_cutoff(&input, 12), None);
+        let input = Input { size: EXAMPLE1_SIZE, coords: Vec::new() };
+        assert_eq!(Day18::find_cutoff(&input, 0), None);
     }
 
     
@@ -239,20 +222,29 @@ mod test {
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 2.
-        let mut d = Day18::new();
-        d.prob_size = 7;
-        d.start_t = 12; // override for test
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(22));
+        let d = Day18::new();
+        let params = Params::new(d.params(), &[("size", "7"), ("t", "12")]).unwrap();
+        assert_eq!(d.part1(EXAMPLE1, &params), Ok(Answer::Numeric(22)));
+
+        let params = Params::new(d.params(), &[("size", "7"), ("t", "100")]).unwrap();
+        assert_eq!(d.part1(EXAMPLE1, &params), Err(SolveError::bad_input(18, "only 25 bytes fall, fewer than t = 100")));
+        let params = Params::new(d.params(), &[("size", "0")]).unwrap();
+        assert_eq!(d.part1("", &params), Err(SolveError::bad_input(18, "parameter size must be at least 1")));
+        assert_eq!(d.part2("", &params), Err(SolveError::bad_input(18, "parameter size must be at least 1")));
     }
 
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
         // Based on the example in part 2.
-        let mut d = Day18::new();
-        d.prob_size = 7;
-        d.start_t = 12; // override for test
-        assert_eq!(d.part2(EXAMPLE1), Answer::String("6,1".to_string()));
+        let d = Day18::new();
+        let params = Params::new(d.params(), &[("size", "7"), ("t", "12")]).unwrap();
+        assert_eq!(d.part2(EXAMPLE1, &params), Ok(Answer::coord(6, 1)));
+
+        for t in ["0", "100"] {
+            let params = Params::new(d.params(), &[("size", "7"), ("t", t)]).unwrap();
+            assert_eq!(d.part2(EXAMPLE1, &params), Ok(Answer::coord(6, 1)));
+        }
     }
     
 }
\ No newline at end of file
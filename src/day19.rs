@@ -1,14 +1,13 @@
 use std::collections::HashMap;
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    static ref PATTERN_RE: Regex = Regex::new("([wubrg]+)").unwrap();
-}
+const DAY: usize = 19;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -18,35 +17,31 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut patterns = Vec::new();
         let mut designs = Vec::new();
 
-        let mut in_designs = false;
-        for line in text.lines() {
-            if !in_designs {
-                if line.trim().len() == 0 {
-                    // Transition to designs
-                    in_designs = true;
-                }
-                else {
-                    // Read patterns
-                    for caps in PATTERN_RE.captures_iter(line) {
-                        patterns.push(caps[1].to_string());
-                    }
-                }
-            }
-            else {
-                // Read a design
-                designs.push(line.trim().to_string());
+        // The towel patterns, then a blank line and the designs.
+        let sections = parse::sections(DAY, text);
+        for line in sections.get(0) {
+            patterns.extend(line.list::<String>(line.text, ',')?);
+        }
+        for line in sections.get(1) {
+            // A design can only contain stripe colors
+            let design = line.text.trim();
+            if let Some(pos) = design.find(|c: char| !"wubrg".contains(c)) {
+                return Err(ParseError::at_char(DAY, line.idx, design, pos, "not a stripe color"));
             }
+            designs.push(design.to_string());
         }
 
-        Input { patterns, designs }
+        Ok(Input { patterns, designs })
     }
 }
 
+solver!(Day19, year: 2024, day: 19, title: "Linen Layout", tags: ["dynamic-programming"]);
+
 pub struct Day19 {
 }
 
@@ -112,16 +107,16 @@ impl Day19 {
 impl<'a> Day for Day19 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::alt_matches(&input))
+        Ok(Answer::Numeric(Self::alt_matches(&input)))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::num_matches(&input))
+        Ok(Answer::Numeric(Self::num_matches(&input)))
     }
 }
 
@@ -131,6 +126,7 @@ mod test {
 
     use crate::day19::{Day19, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -149,7 +145,7 @@ bbrgwb
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.patterns.len(), 8);
         assert_eq!(input.designs.len(), 8);
@@ -159,14 +155,14 @@ bbrgwb
    
     #[test]
     fn test_alt_matches() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(Day19::alt_matches(&input), 6);
     }
     
     #[test]
     fn test_num_matches() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(Day19::num_matches(&input), 16);
     }
@@ -176,7 +172,7 @@ bbrgwb
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day19::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(6));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(6)));
     }
 
     #[test]
@@ -184,7 +180,7 @@ bbrgwb
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day19::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(16));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(16)));
     }
     
 }
\ No newline at end of file
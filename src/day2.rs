@@ -1,14 +1,10 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError, parse_field};
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref LINE_RE: Regex = Regex::new("(\\d+)").unwrap();
-}
+const DAY: usize = 2;
 
 struct Report {
     values: Vec<usize>,
@@ -21,22 +17,26 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut reports: Vec<Report> = Vec::new();
-        for line in text.lines() {
-            let values: Vec<usize> = LINE_RE.find_iter(line)
-                .map(|m| m.as_str()
-                    .parse::<usize>().unwrap())
-                .collect();
-
-            reports.push(Report { values });
+        for (idx, line) in text.lines().enumerate() {
+            // Every space separated field must be a level.
+            let values = line.split_whitespace()
+                .map(|field| parse_field::<usize>(DAY, idx, line, field))
+                .collect::<Result<Vec<usize>, ParseError>>()?;
+
+            if !values.is_empty() {
+                reports.push(Report { values });
+            }
         }
 
-        Input { reports }
+        Ok(Input { reports })
     }
 }
 
+solver!(Day2, year: 2024, day: 2, title: "Red-Nosed Reports", tags: ["lists"]);
+
 pub struct Day2 {
 }
 
@@ -101,20 +101,20 @@ impl Day2 {
 impl Day for Day2 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let num_safe = input.reports.iter().filter(|r| is_safe(&r.values)).count();
 
-        Answer::Numeric(num_safe)
+        Ok(Answer::Numeric(num_safe))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let num_safe = input.reports.iter().filter(|r| is_damped_safe(&r.values)).count();
 
-        Answer::Numeric(num_safe)
+        Ok(Answer::Numeric(num_safe))
     }
 }
 
@@ -124,6 +124,7 @@ mod test {
 
     use crate::day2::{Day2, Input, is_safe, is_damped_safe};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     const EXAMPLE1: &str =
 "7 6 4 2 1
@@ -137,7 +138,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
                 
         assert_eq!(input.reports.len(), 6);
         assert_eq!(input.reports[0].values.len(), 5);
@@ -146,7 +147,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_safety() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         let expected = [true, false, false, false, false, true];
 
@@ -158,7 +159,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_damped_safety() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         let expected = [true, false, false, true, true, true];
 
@@ -172,7 +173,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day2::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(2));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(2)));
     }
 
     #[test]
@@ -180,7 +181,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day2::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(4));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(4)));
     }
     
 }
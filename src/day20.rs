@@ -1,48 +1,37 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::HashMap;
 
 use itertools::Itertools;
 
 use crate::day::{Day, Answer};
+use crate::params::{Param, Params, Value};
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
+use crate::search;
+use crate::timing;
+use crate::registry::solver;
+
+const DAY: usize = 20;
 
 // A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
 struct Input {
-    open: HashSet<(usize, usize)>,
+    open: Grid<bool>,  // true where the track is, false for walls.
     start: (usize, usize),
     end: (usize, usize),
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut open = HashSet::new();
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-
-        for (y, line) in text.lines().enumerate() {
-            let line = line.trim();
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    '.' => {
-                        // Open
-                        open.insert((x, y));
-                    }
-                    'S' => {
-                        // Start
-                        start = (x, y);
-                        open.insert((x, y));
-                    }
-                    'E' => {
-                        // End
-                        end = (x, y);
-                        open.insert((x, y));
-                    }
-                    _ => () // Ignore walls and anything extraneous.
-                }
-            }
-        }
+        let map = Grid::parse(DAY, text, |c| match c {
+            '.' | 'S' | 'E' | '#' => Ok(c),
+            _ => Err("unexpected map character"),
+        })?;
+
+        let start = map.find(&'S').unwrap_or((0, 0));
+        let end = map.find(&'E').unwrap_or((0, 0));
+        let open = map.map(|c| *c != '#');
 
-        Input { open, start, end }
+        Ok(Input { open, start, end })
     }
 }
 
@@ -51,6 +40,8 @@ struct Cheat {
     savings: isize,
 }
 
+solver!(Day20, year: 2024, day: 20, title: "Race Condition", tags: ["grid", "bfs"]);
+
 pub struct Day20 {
 }
 
@@ -62,35 +53,9 @@ impl Day20 {
 
     // Evaluate the distance from coord to every open spot in the map.
     fn dist_from(input: &Input, coord: &(usize, usize)) -> HashMap<(usize, usize), usize> {
-        let mut distances: HashMap<(usize, usize), usize> = HashMap::new();
-
-        let mut to_check: VecDeque<((usize, usize), usize)> = VecDeque::new();
-        to_check.push_back((*coord, 0));
-
-        while !to_check.is_empty() {
-            let ((x, y), dist) = to_check.pop_front().unwrap();
-
-            // If there's already a better way to get to (x, y) ignore this
-            if let Some(other_dist) = distances.get(&(x, y)) {
-                if *other_dist <= dist { continue; }
-            }
-
-            // record this distance
-            distances.insert((x, y), dist);
-
-            // Generate neighbors of (x, y).  If they are open
-            let n = (x, y-1);
-            let s = (x, y+1);
-            let e = (x+1, y);
-            let w = (x-1, y);
-            for neighbor in [n, s, e, w] {
-                if input.open.contains(&neighbor) {
-                    to_check.push_back((neighbor, dist+1));
-                }
-            }
-        }
+        let open = &input.open;
 
-        distances
+        search::bfs(*coord, |pos| open.neighbors4(*pos).filter(|neighbor| open[*neighbor]))
     }
 
     // Test whether a cheat from start_coord to end_coord saves time.
@@ -126,8 +91,9 @@ impl Day20 {
         let orig_dist = from_start.get(&input.end).unwrap();
 
         // Check every pair of open cells as a potential cheat
-        let start_iter = input.open.iter();
-        let end_iter = input.open.iter();
+        let open = input.open.find_all(&true);
+        let start_iter = open.iter();
+        let end_iter = open.iter();
         let cheats = start_iter.cartesian_product(end_iter)
 
             // Make sure start to end distance is allowed
@@ -158,19 +124,25 @@ impl Day20 {
 
 impl<'a> Day for Day20 {
 
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param { name: "threshold", default: Value::Unsigned(100), help: "fewest picoseconds a cheat must save" },
+        ]
+    }
+
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        let n = Day20::num_valid_cheats(&input, 100, 2);
-        Answer::Numeric(n)
+        let n = Day20::num_valid_cheats(&input, params.usize("threshold") as isize, 2);
+        Ok(Answer::Numeric(n))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        let n = Day20::num_valid_cheats(&input, 100, 20);
-        Answer::Numeric(n)
+        let n = Day20::num_valid_cheats(&input, params.usize("threshold") as isize, 20);
+        Ok(Answer::Numeric(n))
     }
 }
 
@@ -180,6 +152,7 @@ mod test {
 
     use crate::day20::{Day20, Input, Cheat};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -203,17 +176,17 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         // Verify inputs were read correctly.
-        assert_eq!(input.open.len(), 85);
+        assert_eq!(input.open.find_all(&true).len(), 85);
         assert_eq!(input.start, (1, 3));
         assert_eq!(input.end, (5, 7));
     }
 
     #[test]
     fn test_cheats() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let cheats = Day20::find_cheats(&input, 2);
 
         assert_eq!(cheats.len(), 14+14+2+4+2+3+1+1+1+1+1);
@@ -221,7 +194,7 @@ mod test {
 
     #[test]
     fn test_num_valid_cheats() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let n = Day20::num_valid_cheats(&input, 20, 2);
 
         assert_eq!(n, 5);
@@ -229,7 +202,7 @@ mod test {
 
     #[test]
     fn test_cheats_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let all_cheats = Day20::find_cheats(&input, 20);
         let best_cheats: Vec<&Cheat> = all_cheats.iter()
             .filter(|c| c.savings >= 50)
@@ -240,7 +213,7 @@ mod test {
 
     #[test]
     fn test_num_valid_cheats_p2() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let n = Day20::num_valid_cheats(&input, 74, 20);
 
         assert_eq!(n, 7);
@@ -251,7 +224,11 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day20::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(0));
+        assert_eq!(d.part1(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(0)));
+
+        // The example's cheats save less time.
+        let params = Params::new(d.params(), &[("threshold", "20")]).unwrap();
+        assert_eq!(d.part1(EXAMPLE1, &params), Ok(Answer::Numeric(5)));
     }
 
     #[test]
@@ -259,7 +236,10 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day20::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(0));
+        assert_eq!(d.part2(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(0)));
+
+        let params = Params::new(d.params(), &[("threshold", "50")]).unwrap();
+        assert_eq!(d.part2(EXAMPLE1, &params), Ok(Answer::Numeric(285)));
     }
     
 }
\ No newline at end of file
@@ -1,14 +1,15 @@
 use std::collections::HashMap;
 
 use lazy_static::lazy_static;
-use regex::Regex;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    static ref LINE_RE: Regex = Regex::new("(\\d{3})A").unwrap();
-}
+const DAY: usize = 21;
 
 struct Code {
     buttons: String,
@@ -22,21 +23,23 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let codes = text.lines()
-            .filter_map(|l| { 
-                if let Some(caps) = LINE_RE.captures(l) {
-                    let num = caps[1].parse::<usize>().unwrap();
-                    Some( Code { buttons: caps[0].to_string(), numeric: num } )
+        let mut codes = Vec::new();
+        for line in parse::lines(DAY, text) {
+            // Three digits and an A.
+            let code = line.text.trim();
+            match code.strip_suffix('A') {
+                Some(digits) if digits.len() == 3 && digits.bytes().all(|b| b.is_ascii_digit()) => {
+                    codes.push( Code { buttons: code.to_string(), numeric: line.parse(digits)? } );
                 }
-                else {
-                    None
+                _ => {
+                    return Err(line.error("expected a code like 029A"));
                 }
-            })
-            .collect();
+            }
+        }
 
-        Input { codes }
+        Ok(Input { codes })
     }
 }
 
@@ -316,6 +319,8 @@ impl<'a> NumKeypad<'a> {
     }
 }
 
+solver!(Day21, year: 2024, day: 21, title: "Keypad Conundrum", tags: ["dynamic-programming", "recursion"]);
+
 pub struct Day21 {
 }
 
@@ -390,16 +395,16 @@ impl Day21 {
 impl<'a> Day for Day21 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::complexity(&input))
+        Ok(Answer::Numeric(Self::complexity(&input)))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::complexity2(&input))
+        Ok(Answer::Numeric(Self::complexity2(&input)))
     }
 }
 
@@ -409,6 +414,9 @@ mod test {
 
     use crate::day21::{Day21, Input, NumKeypad, ArrowKeypad};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    #[cfg(feature = "embedded")]
+    use crate::inputs::embedded_input;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -422,7 +430,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.codes.len(), 5);
         assert_eq!(input.codes[0].buttons, "029A");
@@ -466,7 +474,7 @@ mod test {
 
     #[test]
     fn test_complexity() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         assert_eq!(Day21::complexity(&input), 126384);
     }
 
@@ -475,15 +483,20 @@ mod test {
     fn test_part1_ex1() {
         // Based on the example in part 1.
         let d= Day21::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(126384));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(126384)));
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
+        let Some(text) = embedded_input(21) else {
+            println!("Skipping day 21: no embedded input");
+            return;
+        };
         // Based on the actual input
         let d= Day21::new();
-        let answer = d.part1(data_aoc2024::DAY21_INPUT);
+        let answer = d.part1(text, &Params::default()).unwrap();
         match answer {
             Answer::Numeric(val) => {
                 assert_eq!(val, 206798);
@@ -494,12 +507,17 @@ mod test {
         }
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     // Compute part 2 result on example 2 and confirm expected value.
     fn test_part2() {
+        let Some(text) = embedded_input(21) else {
+            println!("Skipping day 21: no embedded input");
+            return;
+        };
         // Based on the actual input
         let d= Day21::new();
-        let answer = d.part2(data_aoc2024::DAY21_INPUT);
+        let answer = d.part2(text, &Params::default()).unwrap();
         match answer {
             Answer::Numeric(val) => {
                 assert_eq!(val, 251508572750680);
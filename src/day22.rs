@@ -1,6 +1,12 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::day::{Day, Answer};
+use crate::params::{Param, Params, Value};
+use crate::error::{ParseError, SolveError, parse_field};
+use crate::timing;
+use crate::registry::solver;
+
+const DAY: usize = 22;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -9,18 +15,20 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let secrets = text.lines()
-            .map(|line| {
-                line.parse::<usize>().unwrap()
-            })
-            .collect();
+        let mut secrets = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() { continue; }
+            secrets.push(parse_field(DAY, idx, line, line.trim())?);
+        }
 
-        Input { secrets }
+        Ok(Input { secrets })
     }
 }
 
+solver!(Day22, year: 2024, day: 22, title: "Monkey Market", tags: ["simulation", "hashing"]);
+
 pub struct Day22 {
 }
 
@@ -94,40 +102,51 @@ impl Day22 {
         }
     }
 
-    fn most_bananas(input: &Input) -> usize {
+    // The most bananas one sequence of four price changes buys, or None if no buyer's
+    // prices change four times.
+    fn most_bananas(input: &Input, steps: usize) -> Option<usize> {
         let mut bananas_by_seq: HashMap<(i8, i8, i8, i8), usize> = HashMap::new();
 
         // scan the sequences accumulating bananas for each price change sequence.
         for seed in input.secrets.iter() {
-            Day22::scan_seq(*seed, 2000, &mut bananas_by_seq);
+            Day22::scan_seq(*seed, steps, &mut bananas_by_seq);
         }
 
         // Pick the maximum
         bananas_by_seq.iter()
             .map(|(_k, v)| { *v })
-            .max().unwrap()
+            .max()
     }
 }
 
 impl<'a> Day for Day22 {
 
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param { name: "steps", default: Value::Unsigned(2000), help: "secret numbers each buyer generates" },
+        ]
+    }
+
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let value: usize = input.secrets.iter()
             .map(|secret| {
-                Day22::next_value(*secret, 2000)
+                Day22::next_value(*secret, params.usize("steps"))
             })
             .sum();
 
-        Answer::Numeric(value)
+        Ok(Answer::Numeric(value))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Day22::most_bananas(&input))
+        match Day22::most_bananas(&input, params.usize("steps")) {
+            Some(most) => Ok(Answer::Numeric(most)),
+            None => Ok(Answer::None),
+        }
     }
 }
 
@@ -137,6 +156,9 @@ mod test {
 
     use crate::day22::{Day22, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    #[cfg(feature = "embedded")]
+    use crate::inputs::embedded_input;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -156,7 +178,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.secrets.len(), 4);
         assert_eq!(input.secrets[0], 1);
@@ -188,7 +210,7 @@ mod test {
 
     #[test]
     fn test_p1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         let value: usize = input.secrets.iter()
             .map(|secret| {
@@ -201,17 +223,23 @@ mod test {
 
     #[test]
     fn test_most_bananas_ex2() {
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
-        let most = Day22::most_bananas(&input);
-        assert_eq!(most, 23);
+        let most = Day22::most_bananas(&input, 2000);
+        assert_eq!(most, Some(23));
+        assert_eq!(Day22::most_bananas(&input, 3), None);
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_most_bananas() {
-        let input = Input::read(data_aoc2024::DAY22_INPUT);
+        let Some(text) = embedded_input(22) else {
+            println!("Skipping day 22: no embedded input");
+            return;
+        };
+        let input = Input::read(text).unwrap();
 
-        let most = Day22::most_bananas(&input);
+        let most = Day22::most_bananas(&input, 2000).unwrap();
         assert!(most > 1442);
         assert!(most < 1450);
         assert_eq!(most, 1449);
@@ -222,7 +250,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day22::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(37327623));
+        assert_eq!(d.part1(EXAMPLE1, &Params::defaults(d.params())), Ok(Answer::Numeric(37327623)));
     }
 
     #[test]
@@ -230,6 +258,6 @@ mod test {
     fn test_part2_ex2() {
         // Based on the example in part 2.
         let d = Day22::new();
-        assert_eq!(d.part2(EXAMPLE2), Answer::Numeric(23));
+        assert_eq!(d.part2(EXAMPLE2, &Params::defaults(d.params())), Ok(Answer::Numeric(23)));
     }   
 }
\ No newline at end of file
@@ -1,16 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "ab-cd"
-    // captures 1 is "ab", capture 2 is "cd"
-    static ref LINE_RE: Regex = Regex::new("([a-z]{2})-([a-z]{2})").unwrap();
-}
+const DAY: usize = 23;
 
 struct Pair {
     first: String,
@@ -24,16 +21,21 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let pairs = text.lines()
-            .filter_map(|line| { LINE_RE.captures(line)})
-            .map(|caps| { 
-                Pair { first: caps[1].to_string(), second: caps[2].to_string() }
-            })
-            .collect();
-
-        Input { pairs }
+        let mut pairs = Vec::new();
+        for line in parse::lines(DAY, text) {
+            // Two computers, each named by two lowercase letters.
+            let msg = "expected a connection like kh-tc";
+            let (first, second) = line.split_pair("-", msg)?;
+            let is_name = |name: &str| name.len() == 2 && name.bytes().all(|b| b.is_ascii_lowercase());
+            if !is_name(first) || !is_name(second) {
+                return Err(line.error(msg));
+            }
+            pairs.push(Pair { first: first.to_string(), second: second.to_string() });
+        }
+
+        Ok(Input { pairs })
     }
 }
 
@@ -78,7 +80,6 @@ impl Network {
         // Later entries will be computed and added here.
         let fc_n = vec![HashSet::new(), HashSet::new()];
 
-
         Network { nodes, neighbors, fc_n }
     }
 
@@ -90,15 +91,15 @@ impl Network {
 
     // Return a set (repr as a vector) of fully connected sets (another Vec)
     // Nodes are represented by id, usize
-    fn fc(&mut self, n: usize) /* -> HashSet<Vec<usize>> */ {
+    fn fc(&mut self, n: usize) -> Result<(), SolveError> {
         
 
         if n <= 1 {
-            panic!("It don't work like that.");
+            return Err(SolveError::internal(DAY, &format!("fully connected sets of {n} aren't computed")));
         }
         else if n < self.fc_n.len() {
             // Already computed this
-            return;
+            return Ok(());
         }
         else if n == 2 {
             let mut fc_sets = HashSet::new();
@@ -116,7 +117,7 @@ impl Network {
         }
         else {
             // Get the set of fc(n-1) sets
-            self.fc(n-1);
+            self.fc(n-1)?;
             let fc_m1 = &self.fc_n[n-1];
 
             let mut fc_sets = HashSet::new();
@@ -144,17 +145,18 @@ impl Network {
             self.fc_n.push(fc_sets);
         }
 
+        Ok(())
     }
 
     fn is_candidate(&self, node: &usize) -> bool {
         self.nodes[*node].starts_with("t")
     }
 
-    fn max_fc_size(&mut self) -> usize {
+    fn max_fc_size(&mut self) -> Result<usize, SolveError> {
 
         let mut size = 2;
         loop {
-            self.fc(size);
+            self.fc(size)?;
             if self.fc_n[size].len() == 0 {
                 break;
             }
@@ -162,12 +164,12 @@ impl Network {
         };
 
         // Max fc size is now size-1
-        size-1
+        Ok(size-1)
     }
 
-    fn lan_passwd(&mut self) -> String {
+    fn lan_passwd(&mut self) -> Result<String, SolveError> {
         // Analyze the network to determine max fc_size
-        let max_fc = self.max_fc_size();
+        let max_fc = self.max_fc_size()?;
         let mut nodes = Vec::new();
 
         if let Some(s) = self.fc_n[max_fc].iter().next() {
@@ -185,10 +187,12 @@ impl Network {
             passwd.push_str(&nodes[n]);
         }
 
-        passwd
+        Ok(passwd)
     }
 }
 
+solver!(Day23, year: 2024, day: 23, title: "LAN Party", tags: ["graph", "cliques"]);
+
 pub struct Day23 {
 }
 
@@ -198,11 +202,11 @@ impl Day23 {
         Self { }
     }
 
-    fn t_triples(input: &Input) -> usize {
+    fn t_triples(input: &Input) -> Result<usize, SolveError> {
         // Construct a set of all triples by constructing a Network and
         // asking it for all fully connected sets of 3.
         let mut network = Network::new(input);
-        network.fc(3);
+        network.fc(3)?;
         let triples = &network.fc_n[3];
 
         let t_triples: Vec<&Vec<usize>> = triples.iter()
@@ -214,24 +218,24 @@ impl Day23 {
                 })
             }).collect();
 
-        t_triples.len()
+        Ok(t_triples.len())
     }
 }
 
 impl<'a> Day for Day23 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(Self::t_triples(&input))
+        Ok(Answer::Numeric(Self::t_triples(&input)?))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
         let mut network = Network::new(&input);
 
-        Answer::String(network.lan_passwd())
+        Ok(Answer::String(network.lan_passwd()?))
     }
 }
 
@@ -241,6 +245,9 @@ mod test {
 
     use crate::day23::{Day23, Input, Network};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    #[cfg(feature = "embedded")]
+    use crate::inputs::embedded_input;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -281,7 +288,7 @@ td-yn
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.pairs.len(), 32);
         assert_eq!(input.pairs[0].first, "kh");
@@ -290,10 +297,10 @@ td-yn
 
     #[test]
     fn test_fc3() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut network = Network::new(&input);
 
-        network.fc(3);
+        network.fc(3).unwrap();
 
         assert_eq!(network.fc_n[2].len(), 32);
         assert_eq!(network.fc_n[3].len(), 12);
@@ -301,44 +308,53 @@ td-yn
 
     #[test]
     fn test_t_triples() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(Day23::t_triples(&input), 7);
+        assert_eq!(Day23::t_triples(&input), Ok(7));
     }
 
     #[test]
     fn test_max_fc() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut network = Network::new(&input);
 
-        assert_eq!(network.max_fc_size(), 4);
+        assert_eq!(network.max_fc_size(), Ok(4));
     }
 
     #[test]
     fn test_lan_passwd_ex1() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let mut network = Network::new(&input);
 
-        assert_eq!(network.lan_passwd(), "co,de,ka,ta");
+        assert_eq!(network.lan_passwd().unwrap(), "co,de,ka,ta");
     }
 
-
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_max_fc_d23() {
-        let input = Input::read(data_aoc2024::DAY23_INPUT);
+        let Some(text) = embedded_input(23) else {
+            println!("Skipping day 23: no embedded input");
+            return;
+        };
+        let input = Input::read(text).unwrap();
         let mut network = Network::new(&input);
 
-        assert_eq!(network.max_fc_size(), 13);
+        assert_eq!(network.max_fc_size(), Ok(13));
     }
 
     // TODO : Compute LAN password from max_fc
+    #[cfg(feature = "embedded")]
     #[test]
 
     fn test_lan_passwd() {
-        let input = Input::read(data_aoc2024::DAY23_INPUT);
+        let Some(text) = embedded_input(23) else {
+            println!("Skipping day 23: no embedded input");
+            return;
+        };
+        let input = Input::read(text).unwrap();
         let mut network = Network::new(&input);
 
-        assert_eq!(network.lan_passwd(), "az,ed,hz,it,ld,nh,pc,td,ty,ux,wc,yg,zz");
+        assert_eq!(network.lan_passwd().unwrap(), "az,ed,hz,it,ld,nh,pc,td,ty,ux,wc,yg,zz");
     }
     
     #[test]
@@ -346,15 +362,20 @@ td-yn
     fn test_part1_ex1() {
         // Based on the example in part 1.
         let d= Day23::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(7));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(7)));
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1_d23() {
+        let Some(text) = embedded_input(23) else {
+            println!("Skipping day 23: no embedded input");
+            return;
+        };
         // Based on the example in part 1.
         let d= Day23::new();
-        assert_eq!(d.part1(data_aoc2024::DAY23_INPUT), Answer::Numeric(926));
+        assert_eq!(d.part1(text, &Params::default()), Ok(Answer::Numeric(926)));
     }
 
     #[test]
@@ -362,7 +383,7 @@ td-yn
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day23::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::String(String::from("co,de,ka,ta")));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::String(String::from("co,de,ka,ta"))));
     }
     
 }
\ No newline at end of file
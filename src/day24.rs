@@ -1,28 +1,193 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::collections::HashMap;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
+const DAY: usize = 24;
 
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref LINE_RE: Regex = Regex::new("(\\d+)\\s+(\\d+)").unwrap();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    And,
+    Or,
+    Xor,
 }
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
-struct Input {
+impl Op {
+    fn apply(&self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a && b,
+            Op::Or => a || b,
+            Op::Xor => a != b,
+        }
+    }
+}
 
+// A gate, with its wires numbered as in Input::wires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Gate {
+    op: Op,
+    inputs: [usize; 2],
+    output: usize,
+}
+
+// The puzzle input: the wires' starting values, then the gates joining them.
+struct Input {
+    wires: Vec<String>,             // Wire names, numbered in order of appearance.
+    initial: Vec<Option<bool>>,     // The starting value of each wire that has one.
+    gates: Vec<Gate>,
 }
 
 impl Input {
-    fn read(_text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        Input { }
+        let mut wires = Vec::new();
+        let mut ids = HashMap::new();
+        let mut wire = |line: &parse::Line, name: &str| {
+            if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                return Err(line.field_error(name, "expected a wire name like x00 or ntg"));
+            }
+            Ok(*ids.entry(name.to_string()).or_insert_with(|| {
+                wires.push(name.to_string());
+                wires.len()-1
+            }))
+        };
+
+        let sections = parse::sections(DAY, text);
+        let mut values = Vec::new();
+        for line in sections.get(0) {
+            let (name, value) = line.key_value("expected a starting value like x00: 1")?;
+            let value = match value {
+                "0" => false,
+                "1" => true,
+                _ => return Err(line.field_error(value, "expected 0 or 1")),
+            };
+            values.push((wire(line, name)?, value, line));
+        }
+
+        let mut gates = Vec::new();
+        for line in sections.get(1) {
+            let msg = "expected a gate like x00 AND y00 -> z00";
+            let (left, output) = line.split_pair("->", msg)?;
+            let [a, op, b] = left.split_whitespace().collect::<Vec<_>>().try_into().map_err(|_| line.error(msg))?;
+            let op = match op {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "XOR" => Op::Xor,
+                _ => return Err(line.field_error(op, "expected AND, OR or XOR")),
+            };
+            gates.push((Gate { op, inputs: [wire(line, a)?, wire(line, b)?], output: wire(line, output)? }, line));
+        }
+        if sections.len() > 2 {
+            return Err(sections.get(2)[0].error("expected starting values, then gates"));
+        }
+
+        // Each wire is set once, either at the start or by a gate.
+        let mut initial = vec![None; wires.len()];
+        let mut driven = vec![false; wires.len()];
+        for (id, value, line) in values {
+            if initial[id].replace(value).is_some() {
+                return Err(line.error("this wire already has a starting value"));
+            }
+        }
+        for (gate, line) in &gates {
+            if initial[gate.output].is_some() || std::mem::replace(&mut driven[gate.output], true) {
+                return Err(line.error("this wire already has a value"));
+            }
+        }
+
+        Ok(Input { wires, initial, gates: gates.into_iter().map(|(gate, _)| gate).collect() })
+    }
+
+    // The value of every wire, evaluating gates in topological order: each once
+    // both its inputs are known.
+    fn evaluate(&self) -> Result<Vec<bool>, SolveError> {
+        let mut values = self.initial.clone();
+
+        // The gates reading each wire, and the number of inputs each gate waits for.
+        let mut readers: Vec<Vec<usize>> = vec![Vec::new(); self.wires.len()];
+        let mut waiting = vec![0; self.gates.len()];
+        let mut ready = Vec::new();
+        for (n, gate) in self.gates.iter().enumerate() {
+            for input in gate.inputs {
+                if values[input].is_none() {
+                    readers[input].push(n);
+                    waiting[n] += 1;
+                }
+            }
+            if waiting[n] == 0 {
+                ready.push(n);
+            }
+        }
+
+        while let Some(n) = ready.pop() {
+            let gate = &self.gates[n];
+            let [a, b] = gate.inputs.map(|input| values[input].unwrap());
+            values[gate.output] = Some(gate.op.apply(a, b));
+            for reader in &readers[gate.output] {
+                waiting[*reader] -= 1;
+                if waiting[*reader] == 0 {
+                    ready.push(*reader);
+                }
+            }
+        }
+
+        // Gates still waiting read a wire nothing sets, or are in a loop.
+        let waiting: Vec<usize> = (0..self.gates.len()).filter(|n| waiting[*n] > 0).collect();
+        if !waiting.is_empty() {
+            return Err(SolveError::bad_input(DAY, &self.stuck(&values, &waiting)));
+        }
+
+        // Wires that nothing reads might still have no value.
+        Ok(values.iter().map(|v| v.unwrap_or(false)).collect())
+    }
+
+    // Why the waiting gates never got both their inputs.  If none of them reads a
+    // wire that nothing sets, each reads a wire set by another waiting gate, so
+    // following those back from any of them must come round in a loop.
+    fn stuck(&self, values: &[Option<bool>], waiting: &[usize]) -> String {
+        let mut driver = vec![None; self.wires.len()];
+        for (n, gate) in self.gates.iter().enumerate() {
+            driver[gate.output] = Some(n);
+        }
+        let unset = |n: usize| self.gates[n].inputs.into_iter().filter(|w| values[*w].is_none());
+
+        if let Some(w) = waiting.iter().flat_map(|n| unset(*n)).find(|w| driver[*w].is_none()) {
+            return format!("wire {} is read but never set", self.wires[w]);
+        }
+
+        let mut seen = vec![false; self.gates.len()];
+        let mut n = waiting[0];
+        while !seen[n] {
+            seen[n] = true;
+            n = unset(n).find_map(|w| driver[w]).unwrap();
+        }
+
+        format!("the gates form a loop through wire {}", self.wires[self.gates[n].output])
+    }
+
+    // The number on wires named prefix followed by a bit number, like z00, z01, ...
+    fn number(&self, values: &[bool], prefix: char) -> Result<usize, SolveError> {
+        let mut number = 0;
+        for (name, value) in self.wires.iter().zip(values) {
+            let Some(Ok(bit)) = name.strip_prefix(prefix).map(str::parse::<u32>) else {
+                continue;
+            };
+            if bit >= usize::BITS {
+                return Err(SolveError::unsupported(DAY, &format!("wire {name} is beyond bit {}", usize::BITS-1)));
+            }
+            number |= (*value as usize) << bit;
+        }
+
+        Ok(number)
     }
 }
 
+solver!(Day24, year: 2024, day: 24, title: "Crossed Wires", tags: ["logic"]);
+
 pub struct Day24 {
 }
 
@@ -36,16 +201,17 @@ impl Day24 {
 impl<'a> Day for Day24 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let _input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
+        let values = input.evaluate()?;
 
-        Answer::None
+        Ok(Answer::Numeric(input.number(&values, 'z')?))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let _input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let _input = timing::parse(|| Input::read(text))?;
 
-        Answer::None
+        Ok(Answer::None)
     }
 }
 
@@ -53,28 +219,140 @@ impl<'a> Day for Day24 {
 
 mod test {
 
-    use crate::day24::{Day24, Input};
+    use crate::day24::{Day24, Input, Op};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::error::{ParseError, SolveError};
     
     // Example inputs
     const EXAMPLE1: &str = "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+    const EXAMPLE2: &str = "\
+x00: 1
+x01: 0
+x02: 1
+x03: 1
+x04: 0
+y00: 1
+y01: 1
+y02: 1
+y03: 1
+y04: 1
+
+ntg XOR fgs -> mjb
+y02 OR x01 -> tnw
+kwq OR kpj -> z05
+x00 OR x03 -> fst
+tgd XOR rvg -> z01
+vdt OR tnw -> bfw
+bfw AND frj -> z10
+ffh OR nrd -> bqk
+y00 AND y03 -> djm
+y03 OR y00 -> psh
+bqk OR frj -> z08
+tnw OR fst -> frj
+gnj AND tgd -> z11
+bfw XOR mjb -> z00
+x03 OR x00 -> vdt
+gnj AND wpb -> z02
+x04 AND y00 -> kjc
+djm OR pbm -> qhw
+nrd AND vdt -> hwm
+kjc AND fst -> rvg
+y04 OR y02 -> fgs
+y01 AND x02 -> pbm
+ntg OR kjc -> kwq
+psh XOR fgs -> tgd
+qhw XOR tgd -> z09
+pbm OR djm -> kpj
+x03 XOR y03 -> ffh
+x00 XOR y04 -> ntg
+bfw OR bqk -> z06
+nrd XOR fgs -> wpb
+frj XOR qhw -> z04
+bqk OR frj -> z07
+y03 OR x01 -> nrd
+hwm AND bqk -> z03
+tgd XOR rvg -> z12
+tnw OR pbm -> gnj
 ";
 
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let _input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
+
+        assert_eq!(input.wires.len(), 9);
+        assert_eq!(input.initial.iter().filter(|v| v.is_some()).count(), 6);
+        assert_eq!(input.gates.len(), 3);
+        assert_eq!(input.gates[1].op, Op::Xor);
+        assert_eq!(input.wires[input.gates[1].output], "z01");
+
+        let input = Input::read(EXAMPLE2).unwrap();
+        assert_eq!(input.wires.len(), 46);
+        assert_eq!(input.gates.len(), 36);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert_eq!(Input::read("x00: 2\n").err(), Some(ParseError::new(24, 1, 6, "2", "expected 0 or 1")));
+        assert_eq!(Input::read("x00: 1\n\nx00 NAND y00 -> z00\n").err(), Some(ParseError::new(24, 3, 5, "NAND", "expected AND, OR or XOR")));
+        assert_eq!(Input::read("x00: 1\n\nx00 AND -> z00\n").err(), Some(ParseError::new(24, 3, 1, "x00 AND -> z00", "expected a gate like x00 AND y00 -> z00")));
+        assert_eq!(Input::read("x00: 1\n\nx00 AND y00 -> z-0\n").err(), Some(ParseError::new(24, 3, 16, "z-0", "expected a wire name like x00 or ntg")));
+        assert_eq!(Input::read("x00: 1\nx00: 0\n").err(), Some(ParseError::new(24, 2, 1, "x00: 0", "this wire already has a starting value")));
+        assert_eq!(Input::read("x00: 1\n\nx00 AND x00 -> x00\n").err(), Some(ParseError::new(24, 3, 1, "x00 AND x00 -> x00", "this wire already has a value")));
+        assert!(Input::read("x00: 1\n\nx00 OR x00 -> z00\nx00 AND x00 -> z00\n").is_err());
+        assert!(Input::read("x00: 1\n\nx00 OR x00 -> z00\n\nx00: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let input = Input::read(EXAMPLE2).unwrap();
+        let values = input.evaluate().unwrap();
+        let value = |name: &str| values[input.wires.iter().position(|w| w == name).unwrap()];
 
-        // Verify that inputs were read successfully.
-        // assert_eq!(input.left.len(), 6);
+        assert!(value("bfw") && value("bqk") && value("djm") && value("mjb") && value("tnw"));
+        assert!(!value("ffh") && !value("kjc") && !value("ntg") && !value("wpb"));
+        assert_eq!(input.number(&values, 'x'), Ok(0b01101));
+        assert_eq!(input.number(&values, 'y'), Ok(0b11111));
+        assert_eq!(input.number(&values, 'z'), Ok(2024));
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let input = Input::read("x00: 1\n\nx00 AND a -> b\nb OR x00 -> a\nb XOR x00 -> z00\n").unwrap();
+        assert_eq!(input.evaluate(), Err(SolveError::bad_input(24, "the gates form a loop through wire b")));
+
+        let input = Input::read("x00: 1\n\nx00 AND y00 -> z00\n").unwrap();
+        assert_eq!(input.evaluate(), Err(SolveError::bad_input(24, "wire y00 is read but never set")));
+
+        // The first gate waits on the second, which waits on a wire nothing sets.
+        let input = Input::read("x00: 1\n\na OR x00 -> z00\nx00 AND q -> a\n").unwrap();
+        assert_eq!(input.evaluate(), Err(SolveError::bad_input(24, "wire q is read but never set")));
+
+        // The first gate waits on a loop it isn't part of.
+        let input = Input::read("x00: 1\n\nb AND x00 -> z00\nc OR x00 -> b\nd XOR x00 -> c\nc AND c -> d\n").unwrap();
+        assert_eq!(input.evaluate(), Err(SolveError::bad_input(24, "the gates form a loop through wire c")));
     }
 
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
-        // Based on the example in part 1.
+        // Based on the examples in part 1.
         let d= Day24::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::None);
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(4)));
+        assert_eq!(d.part1(EXAMPLE2, &Params::default()), Ok(Answer::Numeric(2024)));
     }
 
     #[test]
@@ -82,7 +360,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day24::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::None);
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::None));
     }
     
-}
\ No newline at end of file
+}
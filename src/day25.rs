@@ -1,14 +1,8 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
-
-
-lazy_static! {
-    // When used on text like "NNNNN   MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref LINE_RE: Regex = Regex::new("(\\d+)\\s+(\\d+)").unwrap();
-}
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::timing;
+use crate::registry::solver;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -17,12 +11,14 @@ struct Input {
 }
 
 impl Input {
-    fn read(_text: &str) -> Input
+    fn read(_text: &str) -> Result<Input, ParseError>
     {
-        Input { }
+        Ok(Input { })
     }
 }
 
+solver!(Day25, year: 2024, day: 25, title: "Code Chronicle", tags: []);
+
 pub struct Day25 {
 }
 
@@ -36,16 +32,16 @@ impl Day25 {
 impl<'a> Day for Day25 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let _input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let _input = timing::parse(|| Input::read(text))?;
 
-        Answer::None
+        Ok(Answer::None)
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let _input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let _input = timing::parse(|| Input::read(text))?;
 
-        Answer::None
+        Ok(Answer::None)
     }
 }
 
@@ -55,6 +51,7 @@ mod test {
 
     use crate::day25::{Day25, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -63,7 +60,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let _input = Input::read(EXAMPLE1);
+        let _input = Input::read(EXAMPLE1).unwrap();
 
         // Verify that inputs were read successfully.
         // assert_eq!(input.left.len(), 6);
@@ -74,7 +71,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day25::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::None);
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::None));
     }
 
     #[test]
@@ -82,7 +79,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day25::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::None);
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::None));
     }
     
 }
\ No newline at end of file
@@ -2,6 +2,12 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError, parse_capture};
+use crate::timing;
+use crate::registry::solver;
+
+const DAY: usize = 3;
 
 
 lazy_static! {
@@ -25,12 +31,13 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    // Anything that isn't an instruction is corrupted memory, which is ignored.
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut instructions: Vec<Instr> = Vec::new();
 
         // For every capture in all the lines, push the appropriate instruction enum.
-        for line in text.lines() {
+        for (idx, line) in text.lines().enumerate() {
             for cap in INSTR_RE.captures_iter(line) {
                 match &cap[0] {
                     "don't()" => {
@@ -44,13 +51,15 @@ impl Input {
                     _ => {
                         // must be MUL
                         // println!("Found MUL: {}", &cap[0]);
-                        instructions.push(Instr::Mul(cap[1].parse::<usize>().unwrap(), cap[2].parse::<usize>().unwrap()));
+                        instructions.push(Instr::Mul(
+                            parse_capture(DAY, idx, line, &cap, 1)?,
+                            parse_capture(DAY, idx, line, &cap, 2)?));
                     }
                 }
             }
         }
 
-        Input { instructions }
+        Ok(Input { instructions })
     }
 
     // sum all the multiplies, regardless of do/don't instructions.
@@ -87,6 +96,8 @@ impl Input {
     }
 }
 
+solver!(Day3, year: 2024, day: 3, title: "Mull It Over", tags: ["regex", "parsing"]);
+
 pub struct Day3 {
 }
 
@@ -101,20 +112,20 @@ impl Day3 {
 impl Day for Day3 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let sum = input.sum_mul_unconditional();
 
-        Answer::Numeric(sum)
+        Ok(Answer::Numeric(sum))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
         let sum = input.sum_mul_conditional();
 
-        Answer::Numeric(sum)
+        Ok(Answer::Numeric(sum))
     }
 }
 
@@ -122,6 +133,7 @@ impl Day for Day3 {
 mod test {
     use crate::day3::{Day3, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Part 1 Example
     const EXAMPLE1: &str = "\
@@ -137,7 +149,7 @@ xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
     // Read and confirm inputs
     fn test_read() {
         // Read example 1
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.instructions.len(), 4);
         assert_eq!(input.sum_mul_unconditional(), 161);
@@ -146,7 +158,7 @@ xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
     #[test]
     fn test_read2() {
         // read example 2
-        let input = Input::read(EXAMPLE2);
+        let input = Input::read(EXAMPLE2).unwrap();
 
         assert_eq!(input.instructions.len(), 6);
         assert_eq!(input.sum_mul_unconditional(), 161);
@@ -158,7 +170,7 @@ xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day3::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(161));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(161)));
     }
 
     #[test]
@@ -166,7 +178,7 @@ xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day3::new();
-        assert_eq!(d.part2(EXAMPLE2), Answer::Numeric(48));
+        assert_eq!(d.part2(EXAMPLE2, &Params::default()), Ok(Answer::Numeric(48)));
     }
     
 }
\ No newline at end of file
@@ -1,6 +1,13 @@
 use lazy_static::lazy_static;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
+use crate::timing;
+use crate::registry::solver;
+
+const DAY: usize = 4;
 
 struct Check {
     // Vec of (row_offset, col_offset, char)
@@ -27,29 +34,20 @@ lazy_static! {
     ];
 }
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
+// A representation of the puzzle inputs: the word search.
 struct Input {
-    // puzzle[row][col] -> char
-    puzzle: Vec<Vec<char>>,
+    puzzle: Grid<char>,
 }
 
 impl Input {
 
 
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut puzzle: Vec<Vec<char>> = Vec::new();
+        // The puzzle has to be rectangular, which the grid checks.
+        let puzzle = Grid::parse_chars(DAY, text)?;
 
-        for line in text.lines() {
-            let mut row: Vec<char> = Vec::new();
-            for c in line.chars() {
-                row.push(c);
-            }
-            puzzle.push(row);
-        }
-
-        Input { puzzle }
+        Ok(Input { puzzle })
     }
 
     fn checks_out(&self, checks: &Vec<Check>, origin: (usize, usize), orientation: usize) -> bool
@@ -57,10 +55,8 @@ impl Input {
         for (row_offs, col_offs, c) in &checks[orientation].checks {
             let row = origin.0+row_offs;
             let col = origin.1+col_offs;
-            if row >= self.puzzle.len() { return false; }
-            if col >= self.puzzle[0].len() { return false; }
 
-            if self.puzzle[row][col] != *c {
+            if self.puzzle.get((col, row)) != Some(c) {
                 return false;
             }
         }
@@ -72,8 +68,8 @@ impl Input {
     {
         let mut count = 0;
 
-        for row in 0..self.puzzle.len() {
-            for col in 0..self.puzzle[0].len() {
+        for row in 0..self.puzzle.height() {
+            for col in 0..self.puzzle.width() {
                 let orientations = XMAS_CHECKS.len();
 
                 for orientation in 0..orientations {
@@ -91,8 +87,8 @@ impl Input {
     {
         let mut count = 0;
 
-        for row in 0..self.puzzle.len() {
-            for col in 0..self.puzzle[0].len() {
+        for row in 0..self.puzzle.height() {
+            for col in 0..self.puzzle.width() {
                 let orientations = MAS_X_CHECKS.len();
 
                 for orientation in 0..orientations {
@@ -107,6 +103,8 @@ impl Input {
     }
 }
 
+solver!(Day4, year: 2024, day: 4, title: "Ceres Search", tags: ["grid", "search"]);
+
 pub struct Day4 {
 }
 
@@ -120,16 +118,16 @@ impl Day4 {
 impl<'a> Day for Day4 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.total_xmas())
+        Ok(Answer::Numeric(input.total_xmas()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.total_mas_x())
+        Ok(Answer::Numeric(input.total_mas_x()))
     }
 }
 
@@ -139,6 +137,7 @@ mod test {
 
     use crate::day4::{Day4, Input, XMAS_CHECKS, MAS_X_CHECKS};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -157,18 +156,18 @@ MXMXAXMASX
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.puzzle.len(), 10);
-        assert_eq!(input.puzzle[0].len(), 10);
-        assert_eq!(input.puzzle[0][0], 'M');
-        assert_eq!(input.puzzle[2][0], 'A');
-        assert_eq!(input.puzzle[0][2], 'M');
+        assert_eq!(input.puzzle.height(), 10);
+        assert_eq!(input.puzzle.width(), 10);
+        assert_eq!(input.puzzle[(0, 0)], 'M');
+        assert_eq!(input.puzzle[(0, 2)], 'A');
+        assert_eq!(input.puzzle[(2, 0)], 'M');
     }
 
     #[test]
     fn test_is_xmas() {
-        let input= Input::read(EXAMPLE1);
+        let input= Input::read(EXAMPLE1).unwrap();
 
         // Is there XMAS at 0, 0 in orientation 0? No.
         assert_eq!(input.checks_out(&XMAS_CHECKS, (0, 0), 0), false);
@@ -182,7 +181,7 @@ MXMXAXMASX
 
     #[test]
     fn test_total_xmas() {
-        let input= Input::read(EXAMPLE1);
+        let input= Input::read(EXAMPLE1).unwrap();
 
         // Count all XMAS.
         assert_eq!(input.total_xmas(), 18);
@@ -190,7 +189,7 @@ MXMXAXMASX
 
     #[test]
     fn test_is_mas_x() {
-        let input= Input::read(EXAMPLE1);
+        let input= Input::read(EXAMPLE1).unwrap();
 
         // Is there MAS_X centered at 1, 1 in orientation 0? No.
         assert_eq!(input.checks_out(&MAS_X_CHECKS, (0, 0), 0), false);
@@ -204,7 +203,7 @@ MXMXAXMASX
 
     #[test]
     fn test_total_mas_x() {
-        let input= Input::read(EXAMPLE1);
+        let input= Input::read(EXAMPLE1).unwrap();
 
         // Count all the MAS X's
         assert_eq!(input.total_mas_x(), 9);
@@ -215,7 +214,7 @@ MXMXAXMASX
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day4::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(18));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(18)));
     }
 
     #[test]
@@ -223,7 +222,7 @@ MXMXAXMASX
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day4::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(9));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(9)));
     }
     
 }
\ No newline at end of file
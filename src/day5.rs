@@ -1,19 +1,13 @@
 use std::cmp::Ordering;
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN|MMMMM"
-    // captures 1, 2 are the two integer inputs, N and M
-    static ref PAIR_RE: Regex = Regex::new("(\\d+)\\|(\\d+)").unwrap();
-
-    // Produces runs of digits from captures_iter().  capture[1] contains digits.
-    static ref INTEGER_RE: Regex = Regex::new("(\\d+)").unwrap();
-}
+const DAY: usize = 5;
 
 // A representation of the puzzle inputs.
 struct Input {
@@ -23,32 +17,22 @@ struct Input {
 
 impl Input {
     // Read input text, constructing Input.
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
         let mut pairs = Vec::new();
         let mut updates = Vec::new();
 
-        let mut in_pairs = true;
-
-        for line in text.lines() {
-            if in_pairs {
-                if let Some(caps) = PAIR_RE.captures(line) {
-                    // Process pair, caps[1] is first elt, caps[2] is second elt.
-                    pairs.push((caps[1].parse().unwrap(), caps[2].parse().unwrap()));
-                }
-                else {
-                    // Input didn't match the pair pattern, it must be the blank line
-                    // switch to processing updates
-                    in_pairs = false;
-                }
-            }
-            else {
-                let update: Vec<usize> = INTEGER_RE.captures_iter(line).map(|caps| caps[1].parse().unwrap()).collect();
-                updates.push(update);
-            }
+        // The ordering rules, then a blank line and the updates.
+        let sections = parse::sections(DAY, text);
+        for line in sections.get(0) {
+            let (first, second) = line.split_pair("|", "expected an ordering rule like 47|53")?;
+            pairs.push((line.parse(first)?, line.parse(second)?));
+        }
+        for line in sections.get(1) {
+            updates.push(line.list(line.text, ',')?);
         }
 
-        Input { pairs, updates }
+        Ok(Input { pairs, updates })
     }
 }
 
@@ -57,7 +41,7 @@ impl Input {
 // checks to see if there's an ordering specified for these two.
 // There's no attempt to check transitive ordering.
 // (Fortunately, this works.  Advent of Code made this easy today!)
-fn simple_verify(pairs: &Vec<(usize, usize)>, update: &Vec<usize>) -> bool {
+fn simple_verify(pairs: &Vec<(usize, usize)>, update: &Vec<usize>) -> Result<bool, SolveError> {
     // println!("Verifying {update:?}");
 
     for window in update.windows(2) {
@@ -65,25 +49,32 @@ fn simple_verify(pairs: &Vec<(usize, usize)>, update: &Vec<usize>) -> bool {
         let b = window[1];
 
         if pairs.contains(&(b, a)) { 
-            return false; 
+            return Ok(false); 
         } else if !pairs.contains(&(a, b)) { 
-            panic!("Simple checks didn't work!");
+            return Err(no_rule(a, b));
         }
     }
 
-    true
+    Ok(true)
 }
 
 // A comparison rule to enable sorting updates according to the ordering rules.
 // Again, there's no attempt to do transitive comparisons.  And, again, we get away with it.
-fn simple_ordering(pairs: &Vec<(usize, usize)>, a: &usize, b: &usize) -> Ordering {
-    if a == b { return Ordering::Equal; }
-    if pairs.contains(&(*a, *b)) { return Ordering::Less; }
-    if pairs.contains(&(*b, *a)) { return Ordering::Greater }
+fn simple_ordering(pairs: &Vec<(usize, usize)>, a: &usize, b: &usize) -> Result<Ordering, SolveError> {
+    if a == b { return Ok(Ordering::Equal); }
+    if pairs.contains(&(*a, *b)) { return Ok(Ordering::Less); }
+    if pairs.contains(&(*b, *a)) { return Ok(Ordering::Greater); }
+
+    Err(no_rule(*a, *b))
+}
 
-    panic!("simple ordering failed you.");
+// The simple checks above need a rule for every pair of pages they look at.
+fn no_rule(a: usize, b: usize) -> SolveError {
+    SolveError::unsupported(DAY, &format!("no rule orders pages {a} and {b}"))
 }
 
+solver!(Day5, year: 2024, day: 5, title: "Print Queue", tags: ["sorting", "graph"]);
+
 pub struct Day5 {
     // This struct exists to support the Day trait.
 }
@@ -98,43 +89,45 @@ impl Day5 {
 impl<'a> Day for Day5 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
-
-        let sum = input.updates.iter()
-        // updates that verify
-            .filter(|upd| simple_verify(&input.pairs, upd)) 
-
-            // take middle element
-            .map(|upd| upd[upd.len()/2]) 
-
-            // sum
-            .sum(); 
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
+
+        let mut sum = 0;
+        for upd in &input.updates {
+            // updates that verify
+            if simple_verify(&input.pairs, upd)? {
+                // take middle element
+                sum += upd[upd.len()/2];
+            }
+        }
 
-        Answer::Numeric(sum)
+        Ok(Answer::Numeric(sum))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        let sum = input.updates.iter()
+        let mut sum = 0;
+        for upd in &input.updates {
             // updates that don't verify
-            .filter(|upd| !simple_verify(&input.pairs, upd)) 
-
-            // clone and sort the update
-            .map(|upd| {                       
-                    let mut c = upd.clone(); 
-                    c.sort_by(|a, b| simple_ordering(&input.pairs, a, b)); 
-                    c 
+            if simple_verify(&input.pairs, upd)? { continue; }
+
+            // clone and sort the update, remembering any pair without a rule
+            let mut missing = None;
+            let mut c = upd.clone();
+            c.sort_by(|a, b| {
+                simple_ordering(&input.pairs, a, b).unwrap_or_else(|e| {
+                    missing = Some(e);
+                    Ordering::Equal
                 })
+            });
+            if let Some(e) = missing { return Err(e); }
 
             // get middle element
-            .map(|upd| upd[upd.len()/2])
-
-            // sum those.
-            .sum();
+            sum += c[c.len()/2];
+        }
 
-        Answer::Numeric(sum)
+        Ok(Answer::Numeric(sum))
     }
 }
 
@@ -144,6 +137,8 @@ mod test {
 
     use crate::day5::{Day5, Input, simple_verify};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::error::SolveError;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -180,7 +175,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.pairs.len(), 21);
         assert_eq!(input.updates.len(), 6);
@@ -188,21 +183,32 @@ mod test {
 
     #[test]
     fn test_simple_verify() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         let expected = vec![true, true, true, false, false, false];
 
         for (n, update) in input.updates.iter().enumerate() {
-            assert_eq!(simple_verify(&input.pairs, update), expected[n]);
+            assert_eq!(simple_verify(&input.pairs, update), Ok(expected[n]));
         }
     }
 
+    #[test]
+    // Pages without an ordering rule between them are reported, not guessed at.
+    fn test_missing_rule() {
+        let d = Day5::new();
+        let text = "47|53\n\n47,53,61\n";
+
+        let expected = Err(SolveError::unsupported(5, "no rule orders pages 53 and 61"));
+        assert_eq!(d.part1(text, &Params::default()), expected);
+        assert_eq!(d.part2(text, &Params::default()), expected);
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day5::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(143));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(143)));
     }
 
     #[test]
@@ -210,7 +216,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day5::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(123));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(123)));
     }
     
 }
\ No newline at end of file
@@ -1,68 +1,56 @@
 use std::collections::HashSet;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::geom::{Dir4, Point};
+use crate::grid::Grid;
+use crate::timing;
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::registry::solver;
 
+const DAY: usize = 6;
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
+
+// A representation of the puzzle inputs: where the blocks are, and where the guard
+// starts.
 struct Input {
-    rows: isize,
-    cols: isize,
-    blocks: HashSet<(isize, isize)>,
-    start_pos: (isize, isize),
-    start_heading: (isize, isize),
+    blocked: Grid<bool>,
+    start_pos: Point,
+    start_heading: Dir4,
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut blocks: HashSet<(isize, isize)> = HashSet::new();
-        let mut start_pos = (0, 0);
-        let mut rows = 0;
-        let mut cols = 0;
-
-        for (row_no_u, line) in text.lines().enumerate() {
-            let row_no = row_no_u as isize;
-            for (col_no_u, c) in line.chars().enumerate() {
-                let col_no = col_no_u as isize;
-                match c {
-                    '#' => {
-                        // record this block
-                        blocks.insert((row_no, col_no));
-                    }
-                    '^' => {
-                        start_pos = (row_no, col_no);
-                    }
-                    _ => ()
-                }
-                if col_no >= cols { cols = col_no + 1; }
-            }
-            if row_no >= rows { rows = row_no + 1; }
-        }
+        let map = Grid::parse(DAY, text, |c| match c {
+            '#' | '^' | '.' => Ok(c),
+            _ => Err("unexpected map character"),
+        })?;
 
-        let start_heading = (-1, 0);
+        let start_pos = Point::from(map.find(&'^').unwrap_or((0, 0)));
+        let start_heading = Dir4::N;
+        let blocked = map.map(|c| *c == '#');
 
-        Input { rows, cols, blocks, start_pos, start_heading }
+        Ok(Input { blocked, start_pos, start_heading })
     }
 
     // do one move from a starting position and heading, returns Some() next position and heading.
     // if moved off the grid, returns None.
-    fn step(&self, pos: &(isize, isize), heading: &(isize, isize), extra_blocker: &Option<(isize, isize)>) -> Option<((isize, isize), (isize, isize))> {
-        // take a Naive step to next_row, next_col
-        let mut next_pos= (pos.0+heading.0, pos.1+heading.1);
+    fn step(&self, pos: &Point, heading: &Dir4, extra_blocker: &Option<Point>) -> Option<(Point, Dir4)> {
+        // take a Naive step to the next position
+        let mut next_pos = *pos + heading.delta();
         let mut next_heading = *heading;
 
         // If we went off grid, return None
-        if (next_pos.0 < 0) | (next_pos.0 >= self.rows) | (next_pos.1 < 0) | (next_pos.1 >= self.cols) {
-            return None;
-        }
+        let blocked = *self.blocked.get_signed(next_pos)?;
 
         // If we are blocked, turn right
-        if self.blocks.contains(&next_pos) | (*extra_blocker == Some(next_pos)) {
+        if blocked | (*extra_blocker == Some(next_pos)) {
             // revert to initial position
             next_pos = *pos;
 
-            next_heading = (heading.1, -heading.0);
+            next_heading = heading.turn_right();
         }
 
         Some((next_pos, next_heading))
@@ -70,7 +58,12 @@ impl Input {
 
     // Number of spaces visited by the simple walk.
     fn num_visited(&self) -> usize {
-        let mut visited: HashSet::<(isize, isize)> = HashSet::new();
+        self.visited().len()
+    }
+
+    // The spaces visited by the simple walk.
+    fn visited(&self) -> HashSet<Point> {
+        let mut visited: HashSet::<Point> = HashSet::new();
 
         let mut position = self.start_pos;
         let mut heading = self.start_heading;
@@ -82,18 +75,13 @@ impl Input {
             visited.insert(position);
         }
 
-        visited.len()
+        visited
     }
 
     // true if it's ok to place a block at position.
-    fn valid_block_space(&self, position:&(isize, isize)) -> bool {
-        // must be on board
-        if (position.0 < 0) | (position.0 >= self.rows) | (position.1 < 0) | (position.1 >= self.cols) {
-            return false;
-        }
-
-        // can't be on another block
-        if self.blocks.contains(position) {
+    fn valid_block_space(&self, position:&Point) -> bool {
+        // must be on board, and can't be on another block
+        if self.blocked.get_signed(*position) != Some(&false) {
             return false;
         }
 
@@ -109,14 +97,14 @@ impl Input {
     // added blocker at <blocker>.
     fn it_loops(
         &self, 
-        early_path: &HashSet<((isize, isize), (isize, isize))>, position:&(isize, isize), 
-        heading:&(isize, isize), 
-        blocker:&(isize, isize)) -> bool {
+        early_path: &HashSet<(Point, Dir4)>, position:&Point, 
+        heading:&Dir4, 
+        blocker:&Point) -> bool {
 
         // step from position, heading until we either retrace a step or fall off board.
         // return true if we start retracing.
 
-        let mut visited:HashSet<((isize, isize), (isize, isize))> = HashSet::new();
+        let mut visited:HashSet<(Point, Dir4)> = HashSet::new();
         let mut position = *position;
         let mut heading = *heading;
 
@@ -137,18 +125,23 @@ impl Input {
 
     // Return number of places a single new block could be place, to create a loop.
     fn num_options(&self) -> usize {
+        self.loopers().len()
+    }
+
+    // The places a single new block could be placed to create a loop.
+    fn loopers(&self) -> HashSet<Point> {
         // Here's the plan: Walk the normal path.  At each step hypothesize a block in front (if valid).  
         // Check whether this leads into a loop by continuing on and detecting overlap.
         // Placing a block is valid if the space is on the grid, isn't already a block or start and 
         // has not been visited earlier.
 
         // Places the path has visited so far
-        let mut visited: HashSet::<(isize, isize)> = HashSet::new();
-        let mut path: HashSet::<((isize, isize), (isize, isize))> = HashSet::new();
+        let mut visited: HashSet::<Point> = HashSet::new();
+        let mut path: HashSet::<(Point, Dir4)> = HashSet::new();
 
         // Places where a block can go and have been found to loop.
-        let mut loopers: HashSet::<(isize, isize)> = HashSet::new();
-        let mut already_checked: HashSet::<(isize, isize)> = HashSet::new();
+        let mut loopers: HashSet::<Point> = HashSet::new();
+        let mut already_checked: HashSet::<Point> = HashSet::new();
 
         // Current position and heading
         let mut position = self.start_pos;
@@ -157,7 +150,7 @@ impl Input {
         path.insert((position, heading));
         
         // Test whether we could block in front of the initial position
-        let next_spot = (position.0+heading.0, position.1+heading.1);
+        let next_spot = position + heading.delta();
         if self.valid_block_space(&next_spot) & 
             !visited.contains(&next_spot) &
             !already_checked.contains(&next_spot) {
@@ -177,7 +170,7 @@ impl Input {
 
             // Test whether we could block in front of this position. 
             // (This duplicates logic above.  This should be refactored.)
-            let next_spot = (position.0+heading.0, position.1+heading.1);
+            let next_spot = position + heading.delta();
             if self.valid_block_space(&next_spot) & 
                 !visited.contains(&next_spot) &
                 !already_checked.contains(&next_spot) {
@@ -190,10 +183,31 @@ impl Input {
             }
         }
 
-        loopers.len()
+        loopers
+    }
+
+    // The map, with the guard's walk and any new blocks that make the guard loop.
+    fn frame(&self, caption: &str, path: &HashSet<Point>, blocks: &HashSet<Point>) -> Frame {
+        let mut cells = self.blocked.map(|blocked| match blocked {
+            true => Cell::new('#', viz::WALL),
+            false => Cell::new('.', viz::BACKGROUND),
+        });
+        for pos in path.iter().filter_map(|p| p.to_pos()) {
+            cells[pos] = Cell::new('X', viz::PATH);
+        }
+        for pos in blocks.iter().filter_map(|p| p.to_pos()) {
+            cells[pos] = Cell::new('O', viz::HIGHLIGHT);
+        }
+        if let Some(start) = self.start_pos.to_pos() {
+            cells[start] = Cell::new('^', viz::START);
+        }
+
+        Frame::new(caption, cells)
     }
 }
 
+solver!(Day6, year: 2024, day: 6, title: "Guard Gallivant", tags: ["grid", "simulation"]);
+
 pub struct Day6 {
 }
 
@@ -207,16 +221,33 @@ impl Day6 {
 impl<'a> Day for Day6 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
+
+        Ok(Answer::Numeric(input.num_visited()))
+    }
+
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.num_visited())
+        Ok(Answer::Numeric(input.num_options()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
 
-        Answer::Numeric(input.num_options())
+impl Visualize for Day6 {
+    fn frames(&self, text: &str, _params: &Params) -> Result<Vec<Frame>, SolveError> {
+        let input = Input::read(text)?;
+        let path = input.visited();
+        let none = HashSet::new();
+
+        Ok(vec![
+            input.frame("the guard's walk", &path, &none),
+            input.frame("new blocks that make the guard loop", &path, &input.loopers()),
+        ])
     }
 }
 
@@ -225,7 +256,10 @@ impl<'a> Day for Day6 {
 mod test {
 
     use crate::day6::{Day6, Input};
+    use crate::geom::{Dir4, Point};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
+    use crate::viz::Visualize;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -244,49 +278,59 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.rows, 10);
-        assert_eq!(input.cols, 10);
-        assert_eq!(input.blocks.len(), 8);
-        assert_eq!(input.start_pos, (6, 4));
-        assert_eq!(input.start_heading, (-1, 0));
+        assert_eq!(input.blocked.height(), 10);
+        assert_eq!(input.blocked.width(), 10);
+        assert_eq!(input.blocked.find_all(&true).len(), 8);
+        assert_eq!(input.start_pos, Point::new(4, 6));
+        assert_eq!(input.start_heading, Dir4::N);
     }
 
     #[test]
     fn test_step() {
-        let input = Input::read(EXAMPLE1);
-
-        assert_eq!(input.step(&(6, 4), &(-1, 0), &None), Some( ((5, 4), (-1, 0)) ) ); // step north
-        assert_eq!(input.step(&(2, 4), &(-1, 0), &None), Some( ((1, 4), (-1, 0)) ) ); // step up to block
-        assert_eq!(input.step(&(1, 4), &(-1, 0), &None), Some( ((1, 4), (0, 1)) ) );  // hit block, turn east
-        assert_eq!(input.step(&(1, 4), &(0, 1), &None), Some( ((1, 5), (0, 1)) ) );   // move east from block
-        assert_eq!(input.step(&(1, 8), &(0, 1), &None), Some( ((1, 8), (1, 0)) ) );   // hit block, turn south
-        assert_eq!(input.step(&(6, 8), &(1, 0), &None), Some( ((6, 8), (0, -1)) ) );  // hit block, turn west
-        assert_eq!(input.step(&(6, 2), &(0, -1), &None), Some( ((6, 2), (-1, 0)) ) ); // hit block, turn north
-        assert_eq!(input.step(&(9, 7), &(1, 0), &None), None );                       // walk off the edge
+        let input = Input::read(EXAMPLE1).unwrap();
+        let p = Point::new;
+
+        assert_eq!(input.step(&p(4, 6), &Dir4::N, &None), Some( (p(4, 5), Dir4::N) ) ); // step north
+        assert_eq!(input.step(&p(4, 2), &Dir4::N, &None), Some( (p(4, 1), Dir4::N) ) ); // step up to block
+        assert_eq!(input.step(&p(4, 1), &Dir4::N, &None), Some( (p(4, 1), Dir4::E) ) ); // hit block, turn east
+        assert_eq!(input.step(&p(4, 1), &Dir4::E, &None), Some( (p(5, 1), Dir4::E) ) ); // move east from block
+        assert_eq!(input.step(&p(8, 1), &Dir4::E, &None), Some( (p(8, 1), Dir4::S) ) ); // hit block, turn south
+        assert_eq!(input.step(&p(8, 6), &Dir4::S, &None), Some( (p(8, 6), Dir4::W) ) ); // hit block, turn west
+        assert_eq!(input.step(&p(2, 6), &Dir4::W, &None), Some( (p(2, 6), Dir4::N) ) ); // hit block, turn north
+        assert_eq!(input.step(&p(7, 9), &Dir4::S, &None), None );                        // walk off the edge
     }
 
     #[test]
     fn test_visited() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.num_visited(), 41);
     }
 
     #[test]
     fn test_num_options() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.num_options(), 6); 
     }
 
+    #[test]
+    fn test_frames() {
+        let frames = Day6::new().frames(EXAMPLE1, &Params::default()).unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].text().matches(['X', '^']).count(), 41);
+        assert_eq!(frames[1].text().matches('O').count(), 6);
+    }
+
     #[test]
     // Compute part 1 result on example 1 and confirm expected value.
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day6::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(41));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(41)));
     }
 
     #[test]
@@ -294,7 +338,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day6::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(6));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(6)));
     }
     
 }
\ No newline at end of file
@@ -1,17 +1,11 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::parse;
+use crate::timing;
+use crate::registry::solver;
 
-
-lazy_static! {
-    // When used on text like "NNNNN: AA BBB CC ..."
-    // captures 1 is the NNNNN part.  capture 2 is AA BBB CC ...
-    static ref LINE_RE: Regex = Regex::new("(\\d+): (.*)").unwrap();
-
-    // captures strings of digits, to be used with captures_iter()
-    static ref NUM_RE: Regex = Regex::new("(\\d+)").unwrap();
-}
+const DAY: usize = 7;
 
 struct Problem {
     result: usize,
@@ -84,24 +78,22 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        // Iterate over input lines, creating a Problem from each one, collect them into a Vec
-        let problems = text.lines()
-            .map(|l| {
-                // split 'result' from components with LINE_RE
-                let caps = LINE_RE.captures(l).unwrap();
-                let result = caps[1].parse::<usize>().unwrap();
-
-                // Iterate over numeric components, collecting them into a vector.
-                let components = NUM_RE.captures_iter(&caps[2])
-                    .map(|num_caps| num_caps[1].parse::<usize>().unwrap())
-                    .collect();
-                Problem {result, components}
-            })
-            .collect();
-
-        Input { problems }
+        // Create a Problem from each line, collect them into a Vec
+        let mut problems = Vec::new();
+        for line in parse::lines(DAY, text) {
+            // split 'result' from components
+            let (result, components) = line.key_value("expected a line like 190: 10 19")?;
+            let result = line.parse(result)?;
+            let components: Vec<usize> = line.words(components)?;
+            if components.is_empty() {
+                return Err(line.error("no components"));
+            }
+            problems.push(Problem {result, components});
+        }
+
+        Ok(Input { problems })
     }
 
     fn sum_solvable(&self, with_concat: bool) -> usize {
@@ -112,6 +104,8 @@ impl Input {
     }
 }
 
+solver!(Day7, year: 2024, day: 7, title: "Bridge Repair", tags: ["recursion", "brute-force"]);
+
 pub struct Day7 {
 }
 
@@ -125,16 +119,16 @@ impl Day7 {
 impl<'a> Day for Day7 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.sum_solvable(false))
+        Ok(Answer::Numeric(input.sum_solvable(false)))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.sum_solvable(true))
+        Ok(Answer::Numeric(input.sum_solvable(true)))
     }
 }
 
@@ -144,6 +138,7 @@ mod test {
 
     use crate::day7::{Day7, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example Inputs
     const EXAMPLE1: &str = "\
@@ -161,7 +156,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         // Check that inputs look ok
         assert_eq!(input.problems.len(), 9);
@@ -171,7 +166,7 @@ mod test {
 
     #[test]
     fn test_solvable_no_concat() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let expected = [true, true, false, false, false, false, false, false, true];
 
         // Make sure all the example problems are tested correctly.
@@ -182,7 +177,7 @@ mod test {
 
     #[test]
     fn test_solvable_with_concat() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
         let expected = [true, true, false, true, true, false, true, false, true];
 
         // Make sure all the example problems are tested correctly.
@@ -194,14 +189,14 @@ mod test {
 
     #[test]
     fn test_sum_solvable_no_concat() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.sum_solvable(false), 3749);
     }
     
     #[test]
     fn test_sum_solvable_with_concat() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.sum_solvable(true), 11387);
     }
@@ -211,7 +206,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day7::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(3749));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(3749)));
     }
 
     #[test]
@@ -219,7 +214,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day7::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(11387));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(11387)));
     }
     
 }
\ No newline at end of file
@@ -1,44 +1,40 @@
 use std::collections::{HashMap, HashSet};
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
+use crate::timing;
+use crate::registry::solver;
 
-// A representation of the puzzle inputs.
-// Today it's just a list (Vec) of Strings, one for each input line.
+const DAY: usize = 8;
+
+// A representation of the puzzle inputs: the map, and where each type of antenna
+// is on it, as (x, y).
 struct Input {
-    width: isize,
-    height: isize,
+    map: Grid<char>,
     antennas: HashMap<char, Vec<(isize, isize)>>,
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let mut width  = 0;
-        let mut height = 0;
-        let mut antennas = HashMap::new();
-
-        for (row_no, line) in text.lines().enumerate() {
-            for (col_no, c) in line.chars().enumerate() {
-                if col_no >= width { width = col_no+1; }
-                if row_no >= height { height = row_no+1; }
-                if c.is_alphanumeric() {
-                    // We found an antenna
-                    // Get reference, in antennas, to this antenna's location.
-                    // (create a new antenna if this is the first.)
-                    let v = match antennas.get_mut(&c) {
-                        Some(vect) => vect,
-                        None => {
-                            antennas.insert(c, Vec::new());
-                            antennas.get_mut(&c).unwrap()
-                        }
-                    };
-
-                    // Add this antenna's location
-                    v.push( (col_no as isize, row_no as isize) );
-                }
+        let map = Grid::parse(DAY, text, |c| {
+            if c.is_alphanumeric() || c == '.' { Ok(c) } else { Err("unexpected map character") }
+        })?;
+
+        let mut antennas: HashMap<char, Vec<(isize, isize)>> = HashMap::new();
+        for ((x, y), c) in map.iter() {
+            if *c != '.' {
+                // We found an antenna, add its location
+                antennas.entry(*c).or_default().push( (x as isize, y as isize) );
             }
         }
 
-        Input { width: width as isize, height: height as isize, antennas }
+        Ok(Input { map, antennas })
+    }
+
+    fn on_map(&self, p: (isize, isize)) -> bool {
+        self.map.get_signed(p).is_some()
     }
 
     fn count_antinodes(&self) -> usize {
@@ -55,14 +51,12 @@ impl Input {
                         let antinode2 = (antennas[j].0-dx, antennas[j].1-dy);
 
                         // Insert antinodes (if they are on the map)
-                        if (antinode1.0 >= 0) & (antinode1.0 < self.width) 
-                           & (antinode1.1 >= 0) & (antinode1.1 < self.height) {
+                        if self.on_map(antinode1) {
                             // valid antinode 1
                             antinode_map.insert(antinode1);
                         }
                         
-                        if (antinode2.0 >= 0) & (antinode2.0 < self.width) 
-                           & (antinode2.1 >= 0) & (antinode2.1 < self.height) {
+                        if self.on_map(antinode2) {
                             // valid antinode 2
                             antinode_map.insert(antinode2);
                         }
@@ -88,14 +82,14 @@ impl Input {
 
                         // Project Antinodes from antenna[i], including the antenna
                         let mut p = (antennas[i].0, antennas[i].1);
-                        while (p.0 >= 0) & (p.0 < self.width) & (p.1 >= 0) & (p.1 < self.height) {
+                        while self.on_map(p) {
                             antinode_map.insert(p);
                             p = (p.0+dx, p.1+dy);
                         }
 
                         // Project Antinodes from antenna[j], including the antenna
                         let mut p = (antennas[j].0, antennas[j].1);
-                        while (p.0 >= 0) & (p.0 < self.width) & (p.1 >= 0) & (p.1 < self.height) {
+                        while self.on_map(p) {
                             antinode_map.insert(p);
                             p = (p.0-dx, p.1-dy);
                         }
@@ -108,6 +102,8 @@ impl Input {
     }
 }
 
+solver!(Day8, year: 2024, day: 8, title: "Resonant Collinearity", tags: ["grid", "geometry"]);
+
 pub struct Day8 {
 }
 
@@ -121,16 +117,16 @@ impl Day8 {
 impl<'a> Day for Day8 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.count_antinodes())
+        Ok(Answer::Numeric(input.count_antinodes()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let input = Input::read(text);
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let input = timing::parse(|| Input::read(text))?;
 
-        Answer::Numeric(input.count_antinodes_updated())
+        Ok(Answer::Numeric(input.count_antinodes_updated()))
     }
 }
 
@@ -140,6 +136,7 @@ mod test {
 
     use crate::day8::{Day8, Input};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example inputs
     const EXAMPLE1: &str = "\
@@ -160,10 +157,10 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
-        assert_eq!(input.width, 12);
-        assert_eq!(input.height, 12);
+        assert_eq!(input.map.width(), 12);
+        assert_eq!(input.map.height(), 12);
         assert_eq!(input.antennas.len(), 2);
         assert_eq!(input.antennas[&'0'].len(), 4);
         assert_eq!(input.antennas[&'0'][0], (8, 1));
@@ -173,7 +170,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_num_antinodes() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.count_antinodes(), 14);
     }
@@ -182,7 +179,7 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_num_antinodes_updated() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.count_antinodes_updated(), 34);
     }
@@ -192,7 +189,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day8::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(14));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(14)));
     }
 
     #[test]
@@ -200,7 +197,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day8::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(34));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(34)));
     }
     
 }
\ No newline at end of file
@@ -1,6 +1,12 @@
 use std::collections::VecDeque;
 
 use crate::day::{Day, Answer};
+use crate::params::Params;
+use crate::error::{ParseError, SolveError};
+use crate::timing;
+use crate::registry::solver;
+
+const DAY: usize = 9;
 
 // A representation of the puzzle inputs.
 // Today it's just a list (Vec) of Strings, one for each input line.
@@ -9,16 +15,19 @@ struct Input {
 }
 
 impl Input {
-    fn read(text: &str) -> Input
+    fn read(text: &str) -> Result<Input, ParseError>
     {
-        let digits = text
-            .trim()
-            .chars()
-            .map(|c| {
-                c.to_digit(10).unwrap() as u8 
+        let line = text.trim();
+        let digits = line
+            .char_indices()
+            .map(|(pos, c)| {
+                match c.to_digit(10) {
+                    Some(d) => Ok(d as u8),
+                    None => Err(ParseError::at_char(DAY, 0, line, pos, "expected a digit")),
+                }
             })
-            .collect();
-        Input { digits}
+            .collect::<Result<Vec<u8>, ParseError>>()?;
+        Ok(Input { digits})
     }
 }
 
@@ -230,6 +239,8 @@ impl Disk {
     }
 }
 
+solver!(Day9, year: 2024, day: 9, title: "Disk Fragmenter", tags: ["simulation"]);
+
 pub struct Day9 {
 }
 
@@ -244,20 +255,20 @@ impl Day9 {
 impl<'a> Day for Day9 {
 
     // Compute Part 1 solution
-    fn part1(&self, text: &str) -> Answer {
-        let mut disk = Disk::new(&Input::read(text));
+    fn part1(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let mut disk = Disk::new(&timing::parse(|| Input::read(text))?);
 
         disk.defrag();
 
-        Answer::Numeric(disk.checksum())
+        Ok(Answer::Numeric(disk.checksum()))
     }
 
-    fn part2(&self, text: &str) -> Answer {
-        let mut disk = Disk::new(&Input::read(text));
+    fn part2(&self, text: &str, _params: &Params) -> Result<Answer, SolveError> {
+        let mut disk = Disk::new(&timing::parse(|| Input::read(text))?);
 
         disk.defrag2();
 
-        Answer::Numeric(disk.checksum())
+        Ok(Answer::Numeric(disk.checksum()))
     }
 }
 
@@ -267,6 +278,7 @@ mod test {
 
     use crate::day9::{Day9, Input, Disk};
     use crate::day::{Day, Answer};
+    use crate::params::Params;
     
     // Example input
     const EXAMPLE1: &str = "\
@@ -276,14 +288,14 @@ mod test {
     #[test]
     // Read and confirm inputs
     fn test_read() {
-        let input = Input::read(EXAMPLE1);
+        let input = Input::read(EXAMPLE1).unwrap();
 
         assert_eq!(input.digits.len(), 19);
     }
 
     #[test]
     fn test_disk() {
-        let disk = Disk::new(&Input::read(EXAMPLE1));
+        let disk = Disk::new(&Input::read(EXAMPLE1).unwrap());
 
         assert_eq!(disk.data_blocks, 28);
         assert_eq!(disk.blocks.len(), 42);
@@ -291,7 +303,7 @@ mod test {
 
     #[test]
     fn test_defrag() {
-        let mut disk = Disk::new(&Input::read(EXAMPLE1));
+        let mut disk = Disk::new(&Input::read(EXAMPLE1).unwrap());
 
         disk.defrag();
 
@@ -301,7 +313,7 @@ mod test {
 
     #[test]
     fn test_scan() {
-        let disk = Disk::new(&Input::read(EXAMPLE1));
+        let disk = Disk::new(&Input::read(EXAMPLE1).unwrap());
         let (free_blocks, data_blocks) = disk.scan_blocks();
 
         assert_eq!(free_blocks[1].len(), 5);
@@ -312,7 +324,7 @@ mod test {
     
     #[test]
     fn test_defrag2() {
-        let mut disk = Disk::new(&Input::read(EXAMPLE1));
+        let mut disk = Disk::new(&Input::read(EXAMPLE1).unwrap());
 
         disk.defrag2();
 
@@ -323,7 +335,7 @@ mod test {
 
     #[test]
     fn test_checksum() {
-        let mut disk = Disk::new(&Input::read(EXAMPLE1));
+        let mut disk = Disk::new(&Input::read(EXAMPLE1).unwrap());
 
         disk.defrag();
 
@@ -335,7 +347,7 @@ mod test {
     fn test_part1() {
         // Based on the example in part 1.
         let d= Day9::new();
-        assert_eq!(d.part1(EXAMPLE1), Answer::Numeric(1928));
+        assert_eq!(d.part1(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(1928)));
     }
 
     #[test]
@@ -343,7 +355,7 @@ mod test {
     fn test_part2() {
         // Based on the example in part 2.
         let d = Day9::new();
-        assert_eq!(d.part2(EXAMPLE1), Answer::Numeric(2858));
+        assert_eq!(d.part2(EXAMPLE1, &Params::default()), Ok(Answer::Numeric(2858)));
     }
     
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::{fmt, str::FromStr};
+
+use regex::Captures;
+
+// An error in the puzzle input, with the location of the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,    // Line number, starting at 1
+    pub column: usize,  // Column number, starting at 1
+    pub text: String,   // The offending text
+    pub msg: String,    // What went wrong
+}
+
+impl ParseError {
+    pub fn new(day: usize, line: usize, column: usize, text: &str, msg: &str) -> ParseError {
+        ParseError { day, line, column, text: text.to_string(), msg: msg.to_string() }
+    }
+
+    // An error with a whole line.  idx is the line's index, counting from 0 as enumerate() does.
+    pub fn in_line(day: usize, idx: usize, line: &str, msg: &str) -> ParseError {
+        ParseError::new(day, idx+1, 1, line, msg)
+    }
+
+    // An error with field, which must be a slice of line.
+    pub fn in_field(day: usize, idx: usize, line: &str, field: &str, msg: &str) -> ParseError {
+        ParseError::new(day, idx+1, column_of(line, field), field, msg)
+    }
+
+    // An error with the character at byte offset pos in line.
+    pub fn at_char(day: usize, idx: usize, line: &str, pos: usize, msg: &str) -> ParseError {
+        let end = pos + line[pos..].chars().next().map_or(0, |c| c.len_utf8());
+        ParseError::in_field(day, idx, line, &line[pos..end], msg)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {} input, line {}, column {}: {} ({:?})",
+            self.day, self.line, self.column, self.msg, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Anything that keeps a day from producing an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),                          // The input couldn't be read.
+    BadInput { day: usize, msg: String },       // The input was read, but breaks the puzzle's rules.
+    Unsupported { day: usize, msg: String },    // The input is valid, but this solution can't handle it.
+    Internal { day: usize, msg: String },       // A bug in the solution.
+    Panicked { day: usize, msg: String },       // The solution panicked.  (Set by the runner.)
+}
+
+impl SolveError {
+    pub fn bad_input(day: usize, msg: &str) -> SolveError {
+        SolveError::BadInput { day, msg: msg.to_string() }
+    }
+
+    pub fn unsupported(day: usize, msg: &str) -> SolveError {
+        SolveError::Unsupported { day, msg: msg.to_string() }
+    }
+
+    pub fn internal(day: usize, msg: &str) -> SolveError {
+        SolveError::Internal { day, msg: msg.to_string() }
+    }
+
+    // A short description, to fit in a results table cell.
+    pub fn summary(&self) -> &'static str {
+        match self {
+            SolveError::Parse(_) => "parse error",
+            SolveError::BadInput { .. } => "bad input",
+            SolveError::Unsupported { .. } => "unsupported",
+            SolveError::Internal { .. } => "internal error",
+            SolveError::Panicked { .. } => "panicked",
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(err: ParseError) -> SolveError {
+        SolveError::Parse(err)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Parse(err) => write!(f, "{err}"),
+            SolveError::BadInput { day, msg } |
+            SolveError::Unsupported { day, msg } |
+            SolveError::Internal { day, msg } |
+            SolveError::Panicked { day, msg } => {
+                write!(f, "day {day} {}: {msg}", self.summary())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolveError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// Column (from 1) where field, a slice of line, starts.
+fn column_of(line: &str, field: &str) -> usize {
+    let offset = (field.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset <= line.len() && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    }
+    else {
+        // Not part of this line after all.
+        1
+    }
+}
+
+// Parse field, which must be a slice of line, as a number or other FromStr type.
+pub fn parse_field<T: FromStr>(day: usize, idx: usize, line: &str, field: &str) -> Result<T, ParseError> {
+    field.parse::<T>()
+        .map_err(|_| ParseError::in_field(day, idx, line, field, "invalid number"))
+}
+
+// Parse capture group n of a regex match on line.
+pub fn parse_capture<T: FromStr>(day: usize, idx: usize, line: &str, caps: &Captures, n: usize) -> Result<T, ParseError> {
+    match caps.get(n) {
+        Some(m) => parse_field(day, idx, line, &line[m.start()..m.end()]),
+        None => Err(ParseError::in_line(day, idx, line, "missing field")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use regex::Regex;
+
+    use crate::error::{ParseError, SolveError, parse_field, parse_capture};
+
+    #[test]
+    fn test_locations() {
+        let line = "abc def";
+
+        assert_eq!(ParseError::in_line(3, 0, line, "bad"), ParseError::new(3, 1, 1, "abc def", "bad"));
+        assert_eq!(ParseError::in_field(3, 4, line, &line[4..], "bad"), ParseError::new(3, 5, 5, "def", "bad"));
+        assert_eq!(ParseError::at_char(3, 4, line, 5, "bad"), ParseError::new(3, 5, 6, "e", "bad"));
+    }
+
+    #[test]
+    fn test_parse_field() {
+        let line = "12 x4 -7";
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        assert_eq!(parse_field::<usize>(1, 0, line, fields[0]), Ok(12));
+        assert_eq!(parse_field::<isize>(1, 0, line, fields[2]), Ok(-7));
+        assert_eq!(parse_field::<usize>(1, 0, line, fields[1]),
+            Err(ParseError::new(1, 1, 4, "x4", "invalid number")));
+    }
+
+    #[test]
+    fn test_parse_capture() {
+        let re = Regex::new("a=(\\d+) b=(\\d+)").unwrap();
+        let line = "a=5 b=99999999999999999999999";
+        let caps = re.captures(line).unwrap();
+
+        assert_eq!(parse_capture::<usize>(2, 6, line, &caps, 1), Ok(5));
+        let err = parse_capture::<usize>(2, 6, line, &caps, 2).unwrap_err();
+        assert_eq!((err.line, err.column), (7, 7));
+        assert_eq!(err.to_string(), "day 2 input, line 7, column 7: invalid number (\"99999999999999999999999\")");
+    }
+
+    #[test]
+    fn test_solve_error() {
+        let err: SolveError = ParseError::new(5, 2, 3, "x", "invalid number").into();
+        assert_eq!(err.summary(), "parse error");
+        assert_eq!(err.to_string(), "day 5 input, line 2, column 3: invalid number (\"x\")");
+
+        let err = SolveError::unsupported(5, "no rule orders 47 and 53");
+        assert_eq!(err.summary(), "unsupported");
+        assert_eq!(err.to_string(), "day 5 unsupported: no rule orders 47 and 53");
+    }
+}
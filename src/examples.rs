@@ -0,0 +1,153 @@
+use std::{fs, path::Path};
+
+use crate::answers::parse_value;
+use crate::day::Answer;
+
+// The worked examples from the puzzles are kept in examples/ as dayN_name.txt,
+// each with a dayN_name.toml beside it giving the expected answers and any
+// parameters the example needs:
+//
+//   part1 = 22
+//   part2 = "6,1"
+//
+//   [params]
+//   size = 7
+//   t = 12
+//
+// A part without an answer isn't checked.  build.rs generates a test for each
+// example (see tests/examples.rs), so adding one is just adding the two files.
+
+pub const EXAMPLES_DIR: &str = "examples";
+
+// The expected answers to parts 1 and 2.
+pub type Answers = [Option<Answer>; 2];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Example {
+    pub day: usize,
+    pub name: String,                   // The file name without ".txt", e.g. "day18_example1"
+    pub input: String,
+    pub answers: Answers,
+    pub params: Vec<(String, String)>,  // As (name, value).
+}
+
+impl Example {
+    // Read an example from its input file and the answers beside it.
+    pub fn load(path: &Path) -> Result<Example, String> {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let Some(day) = example_day(name) else {
+            return Err(format!("{}: example names look like dayN_name.txt", path.display()));
+        };
+
+        let input = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {e}", path.display()))?;
+        let answers_path = path.with_extension("toml");
+        let text = fs::read_to_string(&answers_path)
+            .map_err(|e| format!("couldn't read {}: {e}", answers_path.display()))?;
+        let (answers, params) = parse_answers(&text)
+            .map_err(|e| format!("{}, {e}", answers_path.display()))?;
+
+        Ok(Example { day, name: name.to_string(), input, answers, params })
+    }
+
+    // Solve the example's parts, returning a description of each wrong answer.
+    pub fn check(&self) -> Result<(), String> {
+        let mut wrong = Vec::new();
+        for (part, expected) in [1, 2].into_iter().zip(&self.answers) {
+            let Some(expected) = expected else {
+                continue;
+            };
+            match crate::solve_with(self.day, part, &self.input, &self.params) {
+                Ok(answer) if answer == *expected => (),
+                Ok(answer) => wrong.push(format!("{} part {part}: expected {expected}, got {answer}", self.name)),
+                Err(e) => wrong.push(format!("{} part {part}: expected {expected}, got an error: {e}", self.name)),
+            }
+        }
+
+        if wrong.is_empty() { Ok(()) } else { Err(wrong.join("\n")) }
+    }
+}
+
+// The day of an example named like "day18_example1".
+pub fn example_day(name: &str) -> Option<usize> {
+    let (day, rest) = name.strip_prefix("day")?.split_once('_')?;
+    let day = day.parse::<usize>().ok()?;
+
+    ((1..=25).contains(&day) && !rest.is_empty()).then_some(day)
+}
+
+// Read an example's answers file, giving the answers and the parameters.
+fn parse_answers(text: &str) -> Result<(Answers, Vec<(String, String)>), String> {
+    let mut answers = [None, None];
+    let mut params = Vec::new();
+    let mut in_params = false;
+
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let err = |msg: &str| format!("line {}: {msg} ({line:?})", idx+1);
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line == "[params]" {
+            in_params = true;
+        }
+        else if let Some((key, value)) = line.split_once('=') {
+            let (key, value) = (key.trim(), value.trim());
+            if in_params {
+                let value = match value.split_once('#') {
+                    Some((v, _comment)) => v.trim(),
+                    None => value,
+                };
+                params.push((key.to_string(), value.trim_matches('"').to_string()));
+            }
+            else {
+                let part = match key {
+                    "part1" => 0,
+                    "part2" => 1,
+                    _ => { return Err(err("expected part1 or part2")); }
+                };
+                let Some(answer) = parse_value(value) else {
+                    return Err(err("expected an integer, an array of integers or a quoted string"));
+                };
+                answers[part] = Some(answer);
+            }
+        }
+        else {
+            return Err(err("expected [params] or name = value"));
+        }
+    }
+
+    Ok((answers, params))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::day::Answer;
+    use crate::examples::{example_day, parse_answers};
+
+    #[test]
+    fn test_example_day() {
+        assert_eq!(example_day("day1_example1"), Some(1));
+        assert_eq!(example_day("day18_larger"), Some(18));
+        assert_eq!(example_day("day26_example1"), None);
+        assert_eq!(example_day("day1_"), None);
+        assert_eq!(example_day("example1"), None);
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let (answers, params) = parse_answers("part2 = \"6,1\"\n\n[params]\nsize = 7  # a smaller grid\nt = 12\n").unwrap();
+
+        assert_eq!(answers, [None, Some(Answer::coord(6, 1))]);
+        assert_eq!(params, vec![
+            (String::from("size"), String::from("7")),
+            (String::from("t"), String::from("12")),
+        ]);
+
+        assert!(parse_answers("part3 = 5\n").is_err());
+        assert!(parse_answers("part1 = five\n").is_err());
+        assert!(parse_answers("[day1]\n").is_err());
+    }
+}
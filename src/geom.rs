@@ -0,0 +1,270 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+use crate::grid::Pos;
+
+// Points, vectors and compass directions on the plane, with x to the right and y
+// down, the same way round as a Grid.
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point) -> usize {
+        (*self - *other).manhattan()
+    }
+
+    // Wrap the point onto a width x height torus, so it lands in 0..width, 0..height.
+    pub fn wrap(&self, width: isize, height: isize) -> Point {
+        Point { x: self.x.rem_euclid(width), y: self.y.rem_euclid(height) }
+    }
+
+    // The grid position of the point, if it has one.
+    pub fn to_pos(self) -> Option<Pos> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl Vector {
+    pub const fn new(dx: isize, dy: isize) -> Vector {
+        Vector { dx, dy }
+    }
+
+    pub fn manhattan(&self) -> usize {
+        self.dx.unsigned_abs() + self.dy.unsigned_abs()
+    }
+}
+
+impl From<Pos> for Point {
+    fn from((x, y): Pos) -> Point {
+        Point { x: x as isize, y: y as isize }
+    }
+}
+
+impl From<Point> for (isize, isize) {
+    fn from(p: Point) -> (isize, isize) {
+        (p.x, p.y)
+    }
+}
+
+impl From<Vector> for (isize, isize) {
+    fn from(v: Vector) -> (isize, isize) {
+        (v.dx, v.dy)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point { x: self.x + v.dx, y: self.y + v.dy }
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        self + -v
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector { dx: self.x - other.x, dy: self.y - other.y }
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector { dx: self.dx + other.dx, dy: self.dy + other.dy }
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        self + -other
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector { dx: -self.dx, dy: -self.dy }
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: isize) -> Vector {
+        Vector { dx: self.dx * n, dy: self.dy * n }
+    }
+}
+
+// The four compass directions, clockwise from north (up).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    N, E, S, W,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::N, Dir4::E, Dir4::S, Dir4::W];
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 2) % 4]
+    }
+
+    // One step in this direction.
+    pub fn delta(self) -> Vector {
+        match self {
+            Dir4::N => Vector::new(0, -1),
+            Dir4::E => Vector::new(1, 0),
+            Dir4::S => Vector::new(0, 1),
+            Dir4::W => Vector::new(-1, 0),
+        }
+    }
+}
+
+// The eight compass directions, including the diagonals, clockwise from north.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    N, NE, E, SE, S, SW, W, NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    // Turn 45 degrees clockwise.
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+
+    // Turn 45 degrees anticlockwise.
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+
+    // One step in this direction.
+    pub fn delta(self) -> Vector {
+        match self {
+            Dir8::N => Vector::new(0, -1),
+            Dir8::NE => Vector::new(1, -1),
+            Dir8::E => Vector::new(1, 0),
+            Dir8::SE => Vector::new(1, 1),
+            Dir8::S => Vector::new(0, 1),
+            Dir8::SW => Vector::new(-1, 1),
+            Dir8::W => Vector::new(-1, 0),
+            Dir8::NW => Vector::new(-1, -1),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geom::{Dir4, Dir8, Point, Vector};
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point::new(2, 3);
+        let v = Vector::new(-1, 4);
+
+        assert_eq!(p + v, Point::new(1, 7));
+        assert_eq!(p - v, Point::new(3, -1));
+        assert_eq!(Point::new(1, 7) - p, v);
+        assert_eq!(v * 3, Vector::new(-3, 12));
+        assert_eq!(-v + v, Vector::default());
+        assert_eq!(v.manhattan(), 5);
+        assert_eq!(p.manhattan(&Point::new(-2, 5)), 6);
+
+        let mut q = p;
+        q += v;
+        assert_eq!(q, Point::new(1, 7));
+
+        assert_eq!(Point::from((4, 6)), Point::new(4, 6));
+        assert_eq!(Point::new(4, 6).to_pos(), Some((4, 6)));
+        assert_eq!(Point::new(-1, 6).to_pos(), None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        // Day 14's robot at p=2,4 v=2,-3 on an 11x7 field.
+        let (p, v) = (Point::new(2, 4), Vector::new(2, -3));
+
+        assert_eq!((p + v).wrap(11, 7), Point::new(4, 1));
+        assert_eq!((p + v*2).wrap(11, 7), Point::new(6, 5));
+        assert_eq!((p + v*5).wrap(11, 7), Point::new(1, 3));
+    }
+
+    #[test]
+    fn test_dir4() {
+        assert_eq!(Dir4::N.turn_right(), Dir4::E);
+        assert_eq!(Dir4::W.turn_right(), Dir4::N);
+        assert_eq!(Dir4::N.turn_left(), Dir4::W);
+        assert_eq!(Dir4::E.reverse(), Dir4::W);
+        assert_eq!(Dir4::N.delta(), Vector::new(0, -1));
+
+        for dir in Dir4::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.reverse().delta(), -dir.delta());
+            assert_eq!(Dir8::from(dir).delta(), dir.delta());
+        }
+    }
+
+    #[test]
+    fn test_dir8() {
+        assert_eq!(Dir8::N.turn_right(), Dir8::NE);
+        assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+        assert_eq!(Dir8::SW.reverse(), Dir8::NE);
+        assert_eq!(Dir8::SE.delta(), Vector::new(1, 1));
+
+        for dir in Dir8::ALL {
+            assert_eq!(dir.reverse().delta(), -dir.delta());
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_right().turn_right().turn_right().turn_right(), dir.reverse());
+        }
+    }
+}
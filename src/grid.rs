@@ -0,0 +1,272 @@
+use std::{fmt, ops::{Index, IndexMut}};
+
+use crate::error::ParseError;
+
+// A rectangular grid of cells, for the many puzzles whose input is a map.
+// Positions are (x, y): x is the column and y the row, both counting from the
+// top left corner.  Signed offsets like (0, -1) (one row up) move between them.
+
+pub type Pos = (usize, usize);
+
+// Offsets to the neighbours of a cell: N, E, S, W, and with the diagonals
+// clockwise from N.
+pub const OFFSETS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const OFFSETS8: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,  // Row by row.
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+        where T: Clone
+    {
+        Grid { width, height, cells: vec![fill; width*height] }
+    }
+
+    // Read a grid from text, one row per line, turning each character into a cell
+    // with cell(), which explains any character it doesn't accept.  The grid ends at
+    // the first blank line, or the end of the text.  Rows must all be the same length.
+    pub fn parse<F>(day: usize, text: &str, mut cell: F) -> Result<Grid<T>, ParseError>
+        where F: FnMut(char) -> Result<T, &'static str>
+    {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            let mut row_width = 0;
+            for (pos, c) in line.char_indices() {
+                cells.push(cell(c).map_err(|msg| ParseError::at_char(day, idx, line, pos, msg))?);
+                row_width += 1;
+            }
+
+            if height == 0 {
+                width = row_width;
+            }
+            else if row_width != width {
+                return Err(ParseError::in_line(day, idx, line, "row length differs from the first row"));
+            }
+            height += 1;
+        }
+
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        if x < self.width && y < self.height { Some(&self.cells[y*self.width + x]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+        if x < self.width && y < self.height { Some(&mut self.cells[y*self.width + x]) } else { None }
+    }
+
+    // The cell at a signed position such as a geom::Point, which may be off the grid.
+    pub fn get_signed(&self, pos: impl Into<(isize, isize)>) -> Option<&T> {
+        let (x, y) = pos.into();
+        if x < 0 || y < 0 { None } else { self.get((x as usize, y as usize)) }
+    }
+
+    // The position offset from pos by (dx, dy), or a geom::Vector, if that's on the grid.
+    pub fn step(&self, (x, y): Pos, offset: impl Into<(isize, isize)>) -> Option<Pos> {
+        let (dx, dy) = offset.into();
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    // The neighbours of pos that are on the grid, N, E, S then W.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS4.iter().filter_map(move |offset| self.step(pos, *offset))
+    }
+
+    // The neighbours of pos that are on the grid, including diagonals.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS8.iter().filter_map(move |offset| self.step(pos, *offset))
+    }
+
+    // Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.width*self.height).map(move |i| (i % width, i / width))
+    }
+
+    // Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y*self.width..(y+1)*self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    // The cells in a straight line from start (included) in direction (dx, dy), up
+    // to the edge of the grid.  Rows, columns and diagonals are all rays.
+    pub fn ray(&self, start: Pos, dir: impl Into<(isize, isize)>) -> impl Iterator<Item = (Pos, &T)> {
+        let dir = dir.into();
+        let first = self.get(start).map(|_| start);
+        std::iter::successors(first, move |pos| self.step(*pos, dir))
+            .map(|pos| (pos, &self[pos]))
+    }
+
+    // The positions of every cell equal to value, row by row.
+    pub fn find_all(&self, value: &T) -> Vec<Pos>
+        where T: PartialEq
+    {
+        self.iter().filter(|(_, cell)| *cell == value).map(|(pos, _)| pos).collect()
+    }
+
+    // The position of the first cell equal to value.
+    pub fn find(&self, value: &T) -> Option<Pos>
+        where T: PartialEq
+    {
+        self.iter().find(|(_, cell)| *cell == value).map(|(pos, _)| pos)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+        where F: FnMut(&T) -> U
+    {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    // Draw the grid as text, one line per row.
+    pub fn render<F>(&self, mut f: F) -> String
+        where F: FnMut(&T) -> char
+    {
+        let mut out = String::with_capacity((self.width+1)*self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut f));
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+impl Grid<char> {
+    // Read a grid of any characters.
+    pub fn parse_chars(day: usize, text: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(day, text, Ok)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside the {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside the {width}x{height} grid"),
+        }
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(|c| *c))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::{Grid, OFFSETS8};
+
+    const EXAMPLE: &str = "\
+#.#.
+..S.
+#..#
+
+this isn't part of the grid
+";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse_chars(0, EXAMPLE).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid[(2, 1)], 'S');
+        assert_eq!(grid.get((4, 0)), None);
+        assert_eq!(grid.get_signed((-1, 0)), None);
+        assert_eq!(grid.get_signed((3, 2)), Some(&'#'));
+        assert_eq!(grid.to_string(), "#.#.\n..S.\n#..#\n");
+
+        let walls = Grid::parse(0, EXAMPLE, |c| match c {
+            '#' => Ok(true),
+            '.' | 'S' => Ok(false),
+            _ => Err("unexpected map character"),
+        }).unwrap();
+        assert_eq!(walls.render(|w| if *w { '#' } else { '.' }), "#.#.\n....\n#..#\n");
+
+        let err = Grid::parse(0, "..\n.x\n", |c| if c == '.' { Ok(()) } else { Err("expected a dot") }).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(Grid::parse_chars(0, "...\n..\n").is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::parse_chars(0, EXAMPLE).unwrap();
+
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors4((2, 1)).collect::<Vec<_>>(), vec![(2, 0), (3, 1), (2, 2), (1, 1)]);
+        assert_eq!(grid.neighbors8((3, 2)).collect::<Vec<_>>(), vec![(3, 1), (2, 2), (2, 1)]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.step((0, 0), (-1, 0)), None);
+        assert_eq!(grid.step((0, 0), (3, 2)), Some((3, 2)));
+    }
+
+    #[test]
+    fn test_iteration() {
+        let mut grid = Grid::parse_chars(0, EXAMPLE).unwrap();
+
+        assert_eq!(grid.row(1), &['.', '.', 'S', '.']);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.column(0).collect::<String>(), "#.#");
+        assert_eq!(grid.ray((0, 0), OFFSETS8[3]).map(|(_, c)| *c).collect::<String>(), "#..");
+        assert_eq!(grid.ray((3, 0), (-1, 0)).map(|(pos, _)| pos).collect::<Vec<_>>(), vec![(3, 0), (2, 0), (1, 0), (0, 0)]);
+        assert_eq!(grid.ray((4, 0), (-1, 0)).count(), 0);
+
+        assert_eq!(grid.find(&'S'), Some((2, 1)));
+        assert_eq!(grid.find_all(&'#'), vec![(0, 0), (2, 0), (0, 2), (3, 2)]);
+        assert_eq!(grid.positions().nth(5), Some((1, 1)));
+
+        grid[(2, 1)] = '.';
+        assert_eq!(grid.find(&'S'), None);
+        assert_eq!(grid.map(|c| *c == '#').iter().filter(|(_, wall)| **wall).count(), 4);
+        assert_eq!(Grid::new(2, 2, 'x').to_string(), "xx\nxx\n");
+    }
+}
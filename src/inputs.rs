@@ -0,0 +1,151 @@
+use std::{env, fmt, fs, io::{self, Read}, path::PathBuf};
+
+// Puzzle inputs are found at runtime, rather than being compiled in.
+// For each day, the input comes from the first of these that is available:
+//   1. A file given with --input, or stdin if that file is "-"
+//   2. dayNN.txt in the input directory.  The directory is set by --inputs, or
+//      the AOC_INPUT_DIR environment variable, and is "inputs" by default.
+//   3. The inputs compiled in when built with the "embedded" feature.
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+#[cfg(feature = "embedded")]
+include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+// The input compiled in for this day, if any.
+#[cfg(feature = "embedded")]
+pub fn embedded_input(day: usize) -> Option<&'static str> {
+    EMBEDDED_INPUTS.get(day.wrapping_sub(1)).copied().flatten()
+}
+
+#[cfg(not(feature = "embedded"))]
+pub fn embedded_input(_day: usize) -> Option<&'static str> {
+    None
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { day: usize, path: PathBuf },
+    Io { path: String, err: io::Error },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::NotFound { day, path } => {
+                write!(f, "no input for day {day} (looked for {})", path.display())
+            }
+            InputError::Io { path, err } => {
+                write!(f, "couldn't read {path}: {err}")
+            }
+        }
+    }
+}
+
+enum Source {
+    Stdin,          // Every day reads stdin.
+    File(PathBuf),  // Every day reads this file.
+    Dir(PathBuf),   // Each day reads dayNN.txt in this directory.
+}
+
+pub struct InputProvider {
+    source: Source,
+}
+
+impl InputProvider {
+    // Look for inputs in dir, or in the default directory if None.
+    pub fn new(dir: Option<&str>) -> InputProvider {
+        let dir = match dir {
+            Some(dir) => PathBuf::from(dir),
+            None => match env::var_os(INPUT_DIR_VAR) {
+                Some(dir) => PathBuf::from(dir),
+                None => PathBuf::from(DEFAULT_INPUT_DIR),
+            }
+        };
+
+        InputProvider { source: Source::Dir(dir) }
+    }
+
+    // Read every input from path instead.  "-" means stdin.
+    pub fn from_path(path: &str) -> InputProvider {
+        let source = if path == "-" {
+            Source::Stdin
+        }
+        else {
+            Source::File(PathBuf::from(path))
+        };
+
+        InputProvider { source }
+    }
+
+    // Path this day's input is read from, or None for stdin.
+    pub fn day_path(&self, day: usize) -> Option<PathBuf> {
+        match &self.source {
+            Source::Dir(dir) => Some(dir.join(format!("day{day:02}.txt"))),
+            Source::File(path) => Some(path.clone()),
+            Source::Stdin => None,
+        }
+    }
+
+    // Get the input text for a day.
+    pub fn load(&self, day: usize) -> Result<String, InputError> {
+        match &self.source {
+            Source::Stdin => {
+                let mut text = String::new();
+                match io::stdin().read_to_string(&mut text) {
+                    Ok(_) => Ok(text),
+                    Err(err) => Err(InputError::Io { path: String::from("stdin"), err }),
+                }
+            }
+            Source::File(path) => {
+                fs::read_to_string(path)
+                    .map_err(|err| InputError::Io { path: path.display().to_string(), err })
+            }
+            Source::Dir(_) => {
+                let path = self.day_path(day).unwrap();
+                match fs::read_to_string(&path) {
+                    Ok(text) => Ok(text),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        // Fall back on the compiled in input, if there is one.
+                        match embedded_input(day) {
+                            Some(text) => Ok(text.to_string()),
+                            None => Err(InputError::NotFound { day, path }),
+                        }
+                    }
+                    Err(err) => Err(InputError::Io { path: path.display().to_string(), err }),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::inputs::InputProvider;
+
+    #[test]
+    fn test_day_path() {
+        assert_eq!(InputProvider::from_path("-").day_path(7), None);
+        assert_eq!(InputProvider::from_path("x.txt").day_path(7), Some(PathBuf::from("x.txt")));
+        assert_eq!(InputProvider::new(Some("data")).day_path(7), Some(PathBuf::from("data/day07.txt")));
+    }
+
+    #[test]
+    fn test_load_from_dir() {
+        let dir = env::temp_dir().join(format!("aoc2024_inputs_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day03.txt"), "mul(2,3)\n").unwrap();
+
+        let inputs = InputProvider::new(Some(dir.to_str().unwrap()));
+        assert_eq!(inputs.load(3).unwrap(), "mul(2,3)\n");
+
+        // Day 4 has no file.  (Unless compiled in.)
+        #[cfg(not(feature = "embedded"))]
+        assert!(matches!(inputs.load(4), Err(crate::inputs::InputError::NotFound { day: 4, .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,141 @@
-pub fn test() {
-    println!("Test");
+// The solvers, and the pieces of the runner that go with them.
+//
+// Other programs can solve a puzzle with solve(), or find a day's solver in the
+// registry and use it through the Day trait.
+
+pub mod answers;
+pub mod cli;
+pub mod day;
+pub mod error;
+pub mod examples;
+pub mod geom;
+pub mod grid;
+pub mod inputs;
+pub mod params;
+pub mod parse;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod search;
+pub mod timing;
+pub mod viz;
+pub mod watch;
+
+pub use day::{Answer, Day};
+pub use error::{ParseError, SolveError};
+pub use params::Params;
+
+use registry::YEAR;
+
+// The solvers, see registry.rs
+registry::registry! {
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10,
+    day11, day12, day13, day14, day15, day16, day17, day18, day19, day20,
+    day21, day22, day23, day24, day25,
+}
+
+// Solve one part (1 or 2) of a day's puzzle, given the puzzle input.
+pub fn solve(day: usize, part: usize, input: &str) -> Result<Answer, SolveError> {
+    solve_with(day, part, input, &[] as &[(&str, &str)])
+}
+
+// Solve one part of a day's puzzle with some of its parameters changed from their
+// defaults, e.g. &[("size", "7"), ("t", "12")] for day 18's example.  Parameters
+// the day doesn't take are ignored.
+pub fn solve_with<S: AsRef<str>>(day: usize, part: usize, input: &str, params: &[(S, S)]) -> Result<Answer, SolveError> {
+    let Some(solver) = registry::find(YEAR, day) else {
+        return Err(SolveError::unsupported(day, "there's no solver for this day"));
+    };
+    let params = Params::new(solver.day.params(), params)
+        .map_err(|e| SolveError::bad_input(day, &e))?;
+
+    match part {
+        1 => solver.day.part1(input, &params),
+        2 => solver.day.part2(input, &params),
+        _ => Err(SolveError::unsupported(day, &format!("there's no part {part}"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::answers::{ANSWERS_FILE, AnswerKey};
+    use crate::day::Answer;
+    use crate::inputs::InputProvider;
+    use crate::{SolveError, solve, solve_with};
+
+    // Solve both parts of a day, checking them against the known answers, as the
+    // runner uses them.  Parts without a known answer only have to solve.
+    fn check_day(key: &AnswerKey, day: usize, text: &str) {
+        for part in [1, 2] {
+            let answer = solve(day, part, text).unwrap();
+            match key.get(day, part) {
+                Some(expected) => assert_eq!(answer, *expected, "day {day} part {part}"),
+                None => println!("Day {day} part {part} has no known answer, got {answer:?}"),
+            }
+        }
+    }
+
+    fn answer_key() -> AnswerKey {
+        AnswerKey::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE)).unwrap()
+    }
+
+    #[test]
+    fn test_all() {
+        // Check every day we have an input for.
+        let key = answer_key();
+        let inputs = InputProvider::new(None);
+        for day in 1..25 {
+            let text = match inputs.load(day) {
+                Ok(text) => text,
+                Err(e) => {
+                    println!("Skipping day {day}: {e}");
+                    continue;
+                }
+            };
+            check_day(&key, day, &text);
+        }
+    }
+
+    #[test]
+    fn test_day() {
+        let day = 23;
+        let key = answer_key();
+        let inputs = InputProvider::new(None);
+        if let Ok(text) = inputs.load(day) {
+            check_day(&key, day, &text);
+        }
+    }
+
+    #[test]
+    fn test_unknown_answer() {
+        // Day 24 has no recorded answers, but solves part 1.
+        let key = answer_key();
+        let example = "x00: 1\nx01: 1\ny00: 0\ny01: 1\n\nx00 AND y00 -> z00\nx01 XOR y01 -> z01\n";
+        assert_eq!(key.get(24, 1), None);
+        check_day(&key, 24, example);
+    }
+
+    #[test]
+    fn test_solve() {
+        let example = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+        assert_eq!(solve(1, 1, example), Ok(Answer::Numeric(11)));
+        assert_eq!(solve(1, 2, example), Ok(Answer::Numeric(31)));
+        assert_eq!(solve(1, 3, example), Err(SolveError::unsupported(1, "there's no part 3")));
+        assert_eq!(solve(26, 1, example), Err(SolveError::unsupported(26, "there's no solver for this day")));
+    }
+
+    #[test]
+    fn test_solve_with() {
+        let example = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n1,2\n5,5\n2,5\n6,5\n1,4\n0,4\n6,4\n1,1\n6,1\n1,0\n0,5\n1,6\n2,0\n";
+        let params = [("size", "7"), ("t", "12"), ("threshold", "1")];
+
+        assert_eq!(solve_with(18, 1, example, &params), Ok(Answer::Numeric(22)));
+        assert_eq!(solve_with(18, 2, example, &params), Ok(Answer::coord(6, 1)));
+        assert_eq!(solve_with(18, 1, example, &[("size", "seven")]),
+            Err(SolveError::bad_input(18, "parameter size: expected an unsigned integer, not \"seven\"")));
+    }
+
 }
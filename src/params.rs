@@ -0,0 +1,190 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::error::SolveError;
+
+// Puzzle parameters: numbers the puzzle text gives rather than the input, which
+// are usually different for the examples, like day 18's grid size.
+//
+// A day declares the parameters it takes, with their types and defaults for the
+// real puzzle input, by overriding Day::params().  Each part is passed a Params
+// holding a value for each of them.  Values given on the command line, e.g.
+//   --param size=7 --param t=12
+// replace the defaults for the days that take them.
+
+// A parameter's value.  Values given as text take the type of the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Unsigned(usize),
+    Signed(isize),
+    Bool(bool),
+}
+
+impl Value {
+    // Read a value of the same type as this one.
+    pub fn parse_as(&self, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        let value = match self {
+            Value::Unsigned(_) => text.parse().ok().map(Value::Unsigned),
+            Value::Signed(_) => text.parse().ok().map(Value::Signed),
+            Value::Bool(_) => text.parse().ok().map(Value::Bool),
+        };
+
+        value.ok_or_else(|| format!("expected {}, not {text:?}", self.type_name()))
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Unsigned(_) => "an unsigned integer",
+            Value::Signed(_) => "an integer",
+            Value::Bool(_) => "true or false",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Unsigned(n) => write!(f, "{n}"),
+            Value::Signed(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+// A parameter a day takes.
+pub struct Param {
+    pub name: &'static str,
+    pub default: Value,
+    pub help: &'static str,
+}
+
+// The value of each of a day's parameters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<&'static str, Value>,
+}
+
+impl Params {
+    // The defaults for the declared parameters, replaced by any overrides given as
+    // (name, value) text.  Overrides for parameters that aren't declared are ignored,
+    // since the same overrides go to every day.
+    pub fn new<S: AsRef<str>>(declared: &[Param], overrides: &[(S, S)]) -> Result<Params, String> {
+        let mut values = BTreeMap::new();
+        for param in declared {
+            let mut value = param.default;
+            for (name, text) in overrides {
+                if name.as_ref() == param.name {
+                    value = param.default.parse_as(text.as_ref())
+                        .map_err(|e| format!("parameter {}: {e}", param.name))?;
+                }
+            }
+            values.insert(param.name, value);
+        }
+
+        Ok(Params { values })
+    }
+
+    // The defaults for the declared parameters.
+    pub fn defaults(declared: &[Param]) -> Params {
+        let none: &[(&str, &str)] = &[];
+        Params::new(declared, none).expect("defaults need no parsing")
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.values.get(name).copied()
+    }
+
+    // Typed values.  Asking for a parameter the day didn't declare, or with the wrong
+    // type, is a bug in the day, so these panic.
+    pub fn usize(&self, name: &str) -> usize {
+        match self.get(name) {
+            Some(Value::Unsigned(n)) => n,
+            other => panic!("no unsigned parameter {name} (found {other:?})"),
+        }
+    }
+
+    // An unsigned parameter that can't be 0, like a grid size.  0 is a bad value
+    // for the day's input rather than a bug, so it's an error.
+    pub fn positive(&self, day: usize, name: &str) -> Result<usize, SolveError> {
+        match self.usize(name) {
+            0 => Err(SolveError::bad_input(day, &format!("parameter {name} must be at least 1"))),
+            n => Ok(n),
+        }
+    }
+
+    pub fn isize(&self, name: &str) -> isize {
+        match self.get(name) {
+            Some(Value::Signed(n)) => n,
+            other => panic!("no signed parameter {name} (found {other:?})"),
+        }
+    }
+
+    pub fn bool(&self, name: &str) -> bool {
+        match self.get(name) {
+            Some(Value::Bool(b)) => b,
+            other => panic!("no boolean parameter {name} (found {other:?})"),
+        }
+    }
+}
+
+// Parse "name=value" as given to --param.
+pub fn parse_override(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("expected name=value, not {text:?}")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SolveError;
+    use crate::params::{Param, Params, Value, parse_override};
+
+    const PARAMS: &[Param] = &[
+        Param { name: "size", default: Value::Unsigned(71), help: "grid size" },
+        Param { name: "offset", default: Value::Signed(-1), help: "an offset" },
+        Param { name: "wrap", default: Value::Bool(false), help: "wrap around" },
+    ];
+
+    #[test]
+    fn test_defaults() {
+        let params = Params::defaults(PARAMS);
+
+        assert_eq!(params.usize("size"), 71);
+        assert_eq!(params.isize("offset"), -1);
+        assert!(!params.bool("wrap"));
+        assert_eq!(params.get("t"), None);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let params = Params::new(PARAMS, &[("size", "7"), ("wrap", "true"), ("t", "12")]).unwrap();
+
+        assert_eq!(params.usize("size"), 7);
+        assert_eq!(params.isize("offset"), -1);
+        assert!(params.bool("wrap"));
+        assert_eq!(params.get("t"), None);
+
+        assert_eq!(Params::new(PARAMS, &[("size", "-7")]),
+            Err(String::from("parameter size: expected an unsigned integer, not \"-7\"")));
+        assert!(Params::new(PARAMS, &[("wrap", "1")]).is_err());
+
+        assert_eq!(params.positive(18, "size"), Ok(7));
+        let params = Params::new(PARAMS, &[("size", "0")]).unwrap();
+        assert_eq!(params.positive(18, "size"), Err(SolveError::bad_input(18, "parameter size must be at least 1")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_type() {
+        Params::defaults(PARAMS).isize("size");
+    }
+
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(parse_override("size=7"), Ok((String::from("size"), String::from("7"))));
+        assert_eq!(parse_override(" t = 12 "), Ok((String::from("t"), String::from("12"))));
+        assert!(parse_override("size").is_err());
+        assert!(parse_override("=7").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use crate::error::{ParseError, parse_field};
+
+// Pieces for reading puzzle input a line at a time.  Each Line knows its day and
+// where it is in the input, so anything wrong with it becomes a ParseError that
+// points at the offending text:
+//
+//   for line in parse::lines(DAY, text) {
+//       let [x, y] = line.ints_n("expected a coordinate like 5,4")?;
+//       ...
+//   }
+
+// A non-blank line of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub day: usize,
+    pub idx: usize,     // The line's index, counting from 0 as enumerate() does.
+    pub text: &'a str,
+}
+
+// Every non-blank line of the input.
+pub fn lines(day: usize, text: &str) -> impl Iterator<Item = Line<'_>> {
+    text.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(move |(idx, text)| Line { day, idx, text })
+}
+
+// The input's sections: runs of lines separated by blank lines.
+pub struct Sections<'a> {
+    sections: Vec<Vec<Line<'a>>>,
+}
+
+pub fn sections(day: usize, text: &str) -> Sections<'_> {
+    let mut sections = Vec::new();
+    let mut section = Vec::new();
+
+    for (idx, text) in text.lines().enumerate() {
+        if !text.trim().is_empty() {
+            section.push(Line { day, idx, text });
+        }
+        else if !section.is_empty() {
+            sections.push(std::mem::take(&mut section));
+        }
+    }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+
+    Sections { sections }
+}
+
+impl<'a> Sections<'a> {
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    // The lines of section n, or none if the input doesn't have that many.
+    pub fn get(&self, n: usize) -> &[Line<'a>] {
+        self.sections.get(n).map_or(&[], |s| s.as_slice())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[Line<'a>]> {
+        self.sections.iter().map(|s| s.as_slice())
+    }
+}
+
+impl<'a> Line<'a> {
+    // An error with the whole line.
+    pub fn error(&self, msg: &str) -> ParseError {
+        ParseError::in_line(self.day, self.idx, self.text, msg)
+    }
+
+    // An error with field, which must be a slice of the line.
+    pub fn field_error(&self, field: &str, msg: &str) -> ParseError {
+        ParseError::in_field(self.day, self.idx, self.text, field, msg)
+    }
+
+    // Parse field, a slice of the line, as a number or other FromStr type.
+    pub fn parse<T: FromStr>(&self, field: &str) -> Result<T, ParseError> {
+        parse_field(self.day, self.idx, self.text, field)
+    }
+
+    // Every integer in the line, with its sign, ignoring the text around them:
+    // "p=0,4 v=3,-3" gives 0, 4, 3 and -3.
+    pub fn ints<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        self.ints_in(self.text)
+    }
+
+    // Every integer in field, a slice of the line.
+    pub fn ints_in<T: FromStr>(&self, field: &str) -> Result<Vec<T>, ParseError> {
+        int_fields(field).map(|f| self.parse(f)).collect()
+    }
+
+    // Exactly N integers from the line, or msg as the error.
+    pub fn ints_n<T: FromStr, const N: usize>(&self, msg: &str) -> Result<[T; N], ParseError> {
+        self.ints()?.try_into().map_err(|_| self.error(msg))
+    }
+
+    // The text after prefix, trimmed, or msg as the error if the line doesn't start with it.
+    pub fn after(&self, prefix: &str, msg: &str) -> Result<&'a str, ParseError> {
+        match self.text.trim_start().strip_prefix(prefix) {
+            Some(rest) => Ok(rest.trim()),
+            None => Err(self.error(msg)),
+        }
+    }
+
+    // The trimmed text either side of the first sep, or msg as the error if there isn't one.
+    pub fn split_pair(&self, sep: &str, msg: &str) -> Result<(&'a str, &'a str), ParseError> {
+        match self.text.split_once(sep) {
+            Some((a, b)) => Ok((a.trim(), b.trim())),
+            None => Err(self.error(msg)),
+        }
+    }
+
+    // A "key: value" line.
+    pub fn key_value(&self, msg: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.split_pair(":", msg)
+    }
+
+    // Parse field, a slice of the line, as a list separated by sep: "r, wr, b" or "75,47,61".
+    pub fn list<T: FromStr>(&self, field: &str, sep: char) -> Result<Vec<T>, ParseError> {
+        field.split(sep).map(|f| self.parse(f.trim())).collect()
+    }
+
+    // Parse field, a slice of the line, as a list separated by whitespace.
+    pub fn words<T: FromStr>(&self, field: &str) -> Result<Vec<T>, ParseError> {
+        field.split_whitespace().map(|f| self.parse(f)).collect()
+    }
+}
+
+// The integers in text, as slices of it.  A sign counts if a digit follows it.
+fn int_fields(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let signed = matches!(bytes[pos], b'-' | b'+') && bytes.get(pos+1).is_some_and(u8::is_ascii_digit);
+            if signed || bytes[pos].is_ascii_digit() {
+                let start = pos;
+                pos += 1;
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                return Some(&text[start..pos]);
+            }
+            pos += 1;
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::ParseError;
+    use crate::parse::{self, Line};
+
+    fn line(text: &str) -> Line<'_> {
+        Line { day: 1, idx: 0, text }
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(line("p=0,4 v=3,-3").ints::<isize>(), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(line("Button A: X+94, Y+34").ints::<usize>(), Ok(vec![94, 34]));
+        assert_eq!(line("3   4").ints_n::<usize, 2>("expected two integers"), Ok([3, 4]));
+        assert_eq!(line("ab-cd - 7-2").ints::<isize>(), Ok(vec![7, -2]));
+        assert_eq!(line("no numbers").ints::<isize>(), Ok(vec![]));
+
+        assert_eq!(line("4   x").ints_n::<usize, 2>("expected two integers"),
+            Err(ParseError::new(1, 1, 1, "4   x", "expected two integers")));
+        assert_eq!(line("5,-4").ints::<usize>(), Err(ParseError::new(1, 1, 3, "-4", "invalid number")));
+
+        let l = line("190: 10 19");
+        let (_, rest) = l.key_value("expected a line like 190: 10 19").unwrap();
+        assert_eq!(l.ints_in::<usize>(rest), Ok(vec![10, 19]));
+    }
+
+    #[test]
+    fn test_fields() {
+        let l = line("Register A: 729");
+        assert_eq!(l.key_value("expected a register"), Ok(("Register A", "729")));
+        assert_eq!(l.after("Register", "expected a register"), Ok("A: 729"));
+        assert_eq!(l.after("Program:", "expected a program"), Err(l.error("expected a program")));
+        assert_eq!(line("kh-tc").split_pair("-", "expected a pair"), Ok(("kh", "tc")));
+        assert!(line("kh tc").split_pair("-", "expected a pair").is_err());
+
+        let l = line("75,47, 61");
+        assert_eq!(l.list::<usize>(l.text, ','), Ok(vec![75, 47, 61]));
+        let l = line("r, wr, b");
+        assert_eq!(l.list::<String>(l.text, ','), Ok(vec!["r".to_string(), "wr".to_string(), "b".to_string()]));
+        let l = line("7 6  4");
+        assert_eq!(l.words::<usize>(l.text), Ok(vec![7, 6, 4]));
+        let l = line("7 x");
+        assert_eq!(l.words::<u8>(l.text), Err(ParseError::new(1, 1, 3, "x", "invalid number")));
+    }
+
+    #[test]
+    fn test_sections() {
+        let text = "a\nb\n\n\nc\n\nd\ne\n";
+
+        let lines: Vec<usize> = parse::lines(5, text).map(|l| l.idx).collect();
+        assert_eq!(lines, vec![0, 1, 4, 6, 7]);
+
+        let sections = parse::sections(5, text);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections.get(0).iter().map(|l| l.text).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(sections.get(1)[0], Line { day: 5, idx: 4, text: "c" });
+        assert_eq!(sections.get(2).len(), 2);
+        assert!(sections.get(3).is_empty());
+        assert_eq!(sections.iter().count(), 3);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::SOLVERS;
+use crate::day::Day;
+
+// Every solver declares itself with the solver! macro, giving the puzzle it
+// solves and a little about it.  The registry! macro in lib.rs declares the
+// solvers' modules and collects them into SOLVERS, where the runner and the
+// tests find them.  Adding a day means writing dayN.rs and adding it to registry!.
+
+// The year run when none is given.
+pub const YEAR: usize = 2024;
+
+pub struct Info {
+    pub year: usize,
+    pub day: usize,
+    pub title: &'static str,
+    pub tags: &'static [&'static str],
+}
+
+pub struct Solver {
+    pub info: Info,
+    pub day: &'static dyn Day,
+}
+
+// Declare a module's solver, e.g.
+//   solver!(Day5, year: 2024, day: 5, title: "Print Queue", tags: ["sorting"]);
+macro_rules! solver {
+    ($ty:ident, year: $year:expr, day: $day:expr, title: $title:expr, tags: [$($tag:expr),* $(,)?] $(,)?) => {
+        pub static SOLVER: $crate::registry::Solver = $crate::registry::Solver {
+            info: $crate::registry::Info {
+                year: $year,
+                day: $day,
+                title: $title,
+                tags: &[$($tag),*],
+            },
+            day: &$ty::new(),
+        };
+    };
+}
+pub(crate) use solver;
+
+// Declare solver modules and collect their solvers into SOLVERS.
+macro_rules! registry {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        static SOLVERS: &[&$crate::registry::Solver] = &[$(&$module::SOLVER),*];
+    };
+}
+pub(crate) use registry;
+
+// Every registered solver.
+pub fn solvers() -> impl Iterator<Item = &'static Solver> {
+    SOLVERS.iter().copied()
+}
+
+// The solver for a day of a year, if there is one.
+pub fn find(year: usize, day: usize) -> Option<&'static Solver> {
+    solvers().find(|s| s.info.year == year && s.info.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::registry::{YEAR, find, solvers};
+
+    #[test]
+    fn test_registry() {
+        // Every day of the year has exactly one solver.
+        for day in 1..=25 {
+            assert_eq!(solvers().filter(|s| s.info.year == YEAR && s.info.day == day).count(), 1);
+        }
+        assert!(find(YEAR, 26).is_none());
+        assert!(find(2023, 1).is_none());
+
+        let solver = find(YEAR, 18).unwrap();
+        assert_eq!(solver.info.title, "RAM Run");
+        assert_eq!(solver.day.params().iter().map(|p| p.name).collect::<Vec<_>>(), vec!["size", "t"]);
+        assert!(find(YEAR, 17).unwrap().day.params().is_empty());
+    }
+}
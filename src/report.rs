@@ -0,0 +1,424 @@
+use std::io::{self, Write};
+
+use crate::answers::{AnswerKey, Check};
+use crate::day::Answer;
+use crate::error::SolveError;
+use crate::runner::PartResult;
+use crate::timing::{Stats, format_duration};
+
+// How results are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,  // Fixed width text, for people.
+    Json,   // An array of objects, one per day and part.
+    Csv,    // A header line, then one line per day and part.
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Format> {
+        match s {
+            "table" => Some(Format::Table),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,part,status,check,type,answer,error,runs,parse_ns,solve_ns,min_ns,median_ns,mean_ns,stddev_ns";
+
+// The outcome of one part, as written in JSON and CSV.
+struct Record<'a> {
+    day: usize,
+    part: usize,
+    answer: Result<&'a Answer, String>,  // The answer, or what went wrong.
+    check: Check,
+    stats: Stats,
+}
+
+impl<'a> Record<'a> {
+    fn status(&self) -> &'static str {
+        match self.answer {
+            Ok(Answer::None) => "none",
+            Ok(_) => "ok",
+            Err(_) => "error",
+        }
+    }
+
+    fn answer_type(&self) -> Option<&'static str> {
+        match self.answer {
+            Ok(Answer::None) => Some("none"),
+            Ok(Answer::Numeric(_)) => Some("numeric"),
+            Ok(Answer::Signed(_)) => Some("signed"),
+            Ok(Answer::Big(_)) => Some("big"),
+            Ok(Answer::Tuple(_)) => Some("tuple"),
+            Ok(Answer::Grid(_)) => Some("grid"),
+            Ok(Answer::String(_)) => Some("string"),
+            Err(_) => None,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let answer_type = match self.answer_type() {
+            Some(t) => json_string(t),
+            None => String::from("null"),
+        };
+        let (answer, error) = match &self.answer {
+            Ok(Answer::None) => (String::from("null"), String::from("null")),
+            Ok(answer) => (json_answer(answer), String::from("null")),
+            Err(msg) => (String::from("null"), json_string(msg)),
+        };
+        let s = &self.stats;
+
+        format!("{{\"day\": {}, \"part\": {}, \"status\": {}, \"check\": {}, \"type\": {}, \"answer\": {}, \"error\": {}, \
+                 \"runs\": {}, \"parse_ns\": {}, \"solve_ns\": {}, \"min_ns\": {}, \"median_ns\": {}, \
+                 \"mean_ns\": {}, \"stddev_ns\": {}}}",
+            self.day, self.part, json_string(self.status()), json_string(self.check.name()), answer_type, answer, error,
+            s.runs, s.parse.as_nanos(), s.solve.as_nanos(), s.min.as_nanos(), s.median.as_nanos(),
+            s.mean.as_nanos(), s.stddev.as_nanos())
+    }
+
+    fn to_csv(&self) -> String {
+        let (answer, error) = match &self.answer {
+            Ok(answer) => (csv_field(&answer.to_string()), String::new()),
+            Err(msg) => (String::new(), csv_field(msg)),
+        };
+        let s = &self.stats;
+
+        format!("{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.day, self.part, self.status(), self.check.name(), self.answer_type().unwrap_or(""), answer, error,
+            s.runs, s.parse.as_nanos(), s.solve.as_nanos(), s.min.as_nanos(), s.median.as_nanos(),
+            s.mean.as_nanos(), s.stddev.as_nanos())
+    }
+}
+
+// An answer as a JSON value.  Integers too big for a double to hold exactly (beyond
+// 2^53) are written as strings, tuples as arrays and grids as arrays of rows.
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::None => String::from("null"),
+        Answer::Numeric(n) => json_int(*n as i128),
+        Answer::Signed(n) => json_int(*n as i128),
+        Answer::Big(n) => json_string(&n.to_string()),
+        Answer::Tuple(values) => {
+            let values: Vec<String> = values.iter().map(|v| json_int(*v as i128)).collect();
+            format!("[{}]", values.join(", "))
+        }
+        Answer::Grid(rows) => {
+            let rows: Vec<String> = rows.iter().map(|r| json_string(r)).collect();
+            format!("[{}]", rows.join(", "))
+        }
+        Answer::String(s) => json_string(s),
+    }
+}
+
+// An integer for JSON, quoted if a double can't hold it exactly.
+fn json_int(n: i128) -> String {
+    if n.unsigned_abs() > 1 << 53 { json_string(&n.to_string()) } else { n.to_string() }
+}
+
+// Quote a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+// Quote a CSV field, if it needs it.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    }
+    else {
+        s.to_string()
+    }
+}
+
+// Format an answer for the results table.  Grids don't fit in a column, so
+// they're drawn below the row (see grids).
+fn format_answer(answer: &Result<Answer, SolveError>) -> String {
+    match answer {
+        Ok(Answer::None) => String::from("        -"),
+        Ok(Answer::Grid(rows)) => format!("(grid {}x{})", rows.iter().map(|r| r.chars().count()).max().unwrap_or(0), rows.len()),
+        Ok(answer) => answer.to_string(),
+        Err(e) => e.summary().to_string(),
+    }
+}
+
+// Writes the results of each day as they come in.
+pub struct Reporter<W: Write> {
+    out: W,
+    format: Format,
+    bench: bool,         // Table shows timing statistics rather than answers.
+    records: usize,      // Records written so far. (JSON and CSV)
+    totals: Vec<Stats>,  // Stats of every part that ran successfully. (Benchmark table)
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(out: W, format: Format, bench: bool) -> Reporter<W> {
+        Reporter { out, format, bench, records: 0, totals: Vec::new() }
+    }
+
+    // Write headings.
+    pub fn start(&mut self, runs: usize) -> io::Result<()> {
+        match self.format {
+            Format::Table => {
+                writeln!(self.out, "Advent of Code 2024!\n")?;
+                if self.bench {
+                    writeln!(self.out, "Benchmark, {runs} runs of each part:\n")?;
+                    writeln!(self.out, "{:13} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                        "", "Parse", "Solve", "Min", "Median", "Mean", "Stddev")
+                }
+                else {
+                    writeln!(self.out, "{:7} {:>18}   {:>18}    {:>10} {:>10} {:>10} {:>10}",
+                        "", "Part 1", "Part 2", "Parse 1", "Solve 1", "Parse 2", "Solve 2")
+                }
+            }
+            Format::Json => write!(self.out, "["),
+            Format::Csv => writeln!(self.out, "{CSV_HEADER}"),
+        }
+    }
+
+    // Write the results of the parts run for a day, checked against the known answers.
+    pub fn day(&mut self, day_no: usize, results: &[PartResult], key: &AnswerKey) -> io::Result<()> {
+        match self.format {
+            Format::Table if self.bench => self.bench_rows(day_no, results),
+            Format::Table => self.answer_row(day_no, results, key),
+            _ => {
+                for result in results {
+                    let answer = match &result.answer {
+                        Ok(answer) => Ok(answer),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    let check = key.check(day_no, result.part, result.answer.as_ref().ok());
+                    self.record(Record { day: day_no, part: result.part, answer, check, stats: result.stats() })?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Report a day that couldn't be run, e.g. for lack of input.
+    pub fn not_run(&mut self, day_no: usize, parts: &[usize], msg: &str) -> io::Result<()> {
+        match self.format {
+            Format::Table => writeln!(self.out, "Day {day_no:2}: {msg}"),
+            _ => {
+                for part in parts {
+                    let answer = Err(msg.to_string());
+                    self.record(Record { day: day_no, part: *part, answer, check: Check::Unknown, stats: Stats::default() })?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Write anything that follows the results.
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self.format {
+            Format::Table => {
+                if self.bench {
+                    writeln!(self.out)?;
+                    let totals = Stats::total(&self.totals);
+                    self.stats_row("Total", &totals)?;
+                }
+                writeln!(self.out)
+            }
+            Format::Json => {
+                if self.records > 0 {
+                    writeln!(self.out)?;
+                }
+                writeln!(self.out, "]")
+            }
+            Format::Csv => Ok(()),
+        }
+    }
+
+    fn record(&mut self, record: Record) -> io::Result<()> {
+        match self.format {
+            Format::Json => {
+                let sep = if self.records == 0 { "" } else { "," };
+                write!(self.out, "{sep}\n  {}", record.to_json())?;
+            }
+            _ => {
+                writeln!(self.out, "{}", record.to_csv())?;
+            }
+        }
+        self.records += 1;
+
+        Ok(())
+    }
+
+    // Answers, whether they're right, and the time taken to parse and solve.
+    fn answer_row(&mut self, day_no: usize, results: &[PartResult], key: &AnswerKey) -> io::Result<()> {
+        let mut answers = [String::new(), String::new()];
+        let mut checks = [" ", " "];
+        let mut times = [(String::new(), String::new()), (String::new(), String::new())];
+        for result in results {
+            let stats = result.stats();
+            answers[result.part-1] = format_answer(&result.answer);
+            checks[result.part-1] = key.check(day_no, result.part, result.answer.as_ref().ok()).symbol();
+            times[result.part-1] = (format_duration(stats.parse), format_duration(stats.solve));
+        }
+
+        writeln!(self.out, "Day {day_no:2}: {:>18} {} {:>18} {}  {:>10} {:>10} {:>10} {:>10}",
+            answers[0], checks[0], answers[1], checks[1], times[0].0, times[0].1, times[1].0, times[1].1)?;
+        self.grids(results)?;
+        self.errors(results)
+    }
+
+    // Draw any grid answers below a day's row.
+    fn grids(&mut self, results: &[PartResult]) -> io::Result<()> {
+        for result in results {
+            if let Ok(Answer::Grid(rows)) = &result.answer {
+                writeln!(self.out, "        Part {}:", result.part)?;
+                for row in rows {
+                    writeln!(self.out, "        {row}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Timing statistics for each part.
+    fn bench_rows(&mut self, day_no: usize, results: &[PartResult]) -> io::Result<()> {
+        for result in results {
+            let label = format!("Day {day_no:2} part {}", result.part);
+            match &result.answer {
+                Ok(_) => {
+                    let stats = result.stats();
+                    self.stats_row(&label, &stats)?;
+                    self.totals.push(stats);
+                }
+                Err(e) => {
+                    writeln!(self.out, "{label:13} {:>10}", e.summary())?;
+                }
+            }
+        }
+        self.errors(results)
+    }
+
+    fn stats_row(&mut self, label: &str, stats: &Stats) -> io::Result<()> {
+        writeln!(self.out, "{label:13} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            format_duration(stats.parse), format_duration(stats.solve),
+            format_duration(stats.min), format_duration(stats.median),
+            format_duration(stats.mean), format_duration(stats.stddev))
+    }
+
+    // Explain any failures below a day's rows.  Both parts read the same input, so the
+    // same error is often reported by both; only show it once.
+    fn errors(&mut self, results: &[PartResult]) -> io::Result<()> {
+        let mut shown: Vec<&SolveError> = Vec::new();
+        for result in results {
+            if let Err(e) = &result.answer && !shown.contains(&e) {
+                writeln!(self.out, "        {e}")?;
+                shown.push(e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::answers::AnswerKey;
+    use crate::day::Answer;
+    use crate::error::SolveError;
+    use crate::report::{Format, Reporter, csv_field, format_answer, json_answer, json_string};
+    use crate::runner::PartResult;
+    use crate::timing::Timing;
+
+    fn results() -> Vec<PartResult> {
+        let timing = Timing { parse: Duration::from_nanos(100), solve: Duration::from_nanos(2000) };
+        vec![
+            PartResult { part: 1, answer: Ok(Answer::String(String::from("4,6,3"))), timings: vec![timing] },
+            PartResult { part: 2, answer: Err(SolveError::bad_input(17, "illegal combo operand 7 at 0")), timings: vec![timing] },
+        ]
+    }
+
+    fn report(format: Format) -> String {
+        let mut out = Vec::new();
+        let key = AnswerKey::parse("[day17]\npart1 = \"4,6,3\"\n").unwrap();
+        let mut reporter = Reporter::new(&mut out, format, false);
+        reporter.start(1).unwrap();
+        reporter.day(17, &results(), &key).unwrap();
+        reporter.not_run(18, &[1], "no input for day 18").unwrap();
+        reporter.finish().unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(csv_field("24,48"), "\"24,48\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("plain"), "plain");
+    }
+
+    #[test]
+    fn test_answers() {
+        let grid = Answer::Grid(vec![String::from("#.."), String::from(".#.")]);
+
+        assert_eq!(json_answer(&Answer::Signed(-3)), "-3");
+        assert_eq!(json_answer(&Answer::Numeric(1 << 53)), "9007199254740992");
+        assert_eq!(json_answer(&Answer::Numeric((1 << 53) + 1)), "\"9007199254740993\"");
+        assert_eq!(json_answer(&Answer::Signed(-(1 << 60))), "\"-1152921504606846976\"");
+        assert_eq!(json_answer(&Answer::coord(1 << 54, 48)), "[\"18014398509481984\", 48]");
+        assert_eq!(json_answer(&"123456789012345678901234567890".parse().unwrap()), "\"123456789012345678901234567890\"");
+        assert_eq!(json_answer(&Answer::coord(24, 48)), "[24, 48]");
+        assert_eq!(json_answer(&grid), "[\"#..\", \".#.\"]");
+
+        assert_eq!(format_answer(&Ok(Answer::coord(24, 48))), "24,48");
+        assert_eq!(format_answer(&Ok(grid)), "(grid 3x2)");
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(report(Format::Json), "\
+[
+  {\"day\": 17, \"part\": 1, \"status\": \"ok\", \"check\": \"correct\", \"type\": \"string\", \"answer\": \"4,6,3\", \"error\": null, \"runs\": 1, \"parse_ns\": 100, \"solve_ns\": 2000, \"min_ns\": 2100, \"median_ns\": 2100, \"mean_ns\": 2100, \"stddev_ns\": 0},
+  {\"day\": 17, \"part\": 2, \"status\": \"error\", \"check\": \"unknown\", \"type\": null, \"answer\": null, \"error\": \"day 17 bad input: illegal combo operand 7 at 0\", \"runs\": 1, \"parse_ns\": 100, \"solve_ns\": 2000, \"min_ns\": 2100, \"median_ns\": 2100, \"mean_ns\": 2100, \"stddev_ns\": 0},
+  {\"day\": 18, \"part\": 1, \"status\": \"error\", \"check\": \"unknown\", \"type\": null, \"answer\": null, \"error\": \"no input for day 18\", \"runs\": 0, \"parse_ns\": 0, \"solve_ns\": 0, \"min_ns\": 0, \"median_ns\": 0, \"mean_ns\": 0, \"stddev_ns\": 0}
+]
+");
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(report(Format::Csv), "\
+day,part,status,check,type,answer,error,runs,parse_ns,solve_ns,min_ns,median_ns,mean_ns,stddev_ns
+17,1,ok,correct,string,\"4,6,3\",,1,100,2000,2100,2100,2100,0
+17,2,error,unknown,,,day 17 bad input: illegal combo operand 7 at 0,1,100,2000,2100,2100,2100,0
+18,1,error,unknown,,,no input for day 18,0,0,0,0,0,0,0
+");
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(report(Format::Table), "\
+Advent of Code 2024!
+
+                    Part 1               Part 2       Parse 1    Solve 1    Parse 2    Solve 2
+Day 17:              4,6,3 ✓          bad input ?      100 ns     2.0 µs     100 ns     2.0 µs
+        day 17 bad input: illegal combo operand 7 at 0
+Day 18: no input for day 18
+
+");
+    }
+}
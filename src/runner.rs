@@ -0,0 +1,167 @@
+use std::{panic::{self, AssertUnwindSafe}, sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc}, thread};
+
+use crate::cli::Options;
+use crate::day::Answer;
+use crate::error::SolveError;
+use crate::timing::{self, Stats, Timing};
+
+// The outcome of running one part of a day, one or more times.
+pub struct PartResult {
+    pub part: usize,
+    pub answer: Result<Answer, SolveError>,  // From the last run.
+    pub timings: Vec<Timing>,                // One for each run.
+}
+
+impl PartResult {
+    pub fn stats(&self) -> Stats {
+        Stats::new(&self.timings)
+    }
+}
+
+// Run one part of a day, once, with parameters given as (name, value).  A panic
+// becomes an error, so one broken day doesn't stop the rest.
+pub fn run_part(day_no: usize, part: usize, text: &str, params: &[(String, String)]) -> (Result<Answer, SolveError>, Timing) {
+    let (result, timing) = timing::time(|| {
+        panic::catch_unwind(AssertUnwindSafe(|| crate::solve_with(day_no, part, text, params)))
+    });
+
+    let answer = match result {
+        Ok(answer) => answer,
+        Err(payload) => {
+            let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+                s.to_string()
+            }
+            else if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            }
+            else {
+                String::from("unknown panic")
+            };
+            Err(SolveError::Panicked { day: day_no, msg })
+        }
+    };
+
+    (answer, timing)
+}
+
+// Run one part of a day repeatedly.  A part that fails isn't repeated.
+pub fn run_repeated(day_no: usize, part: usize, text: &str, params: &[(String, String)], repeat: usize) -> PartResult {
+    let mut timings = Vec::new();
+    loop {
+        let (answer, timing) = run_part(day_no, part, text, params);
+        timings.push(timing);
+
+        if answer.is_err() || timings.len() >= repeat {
+            return PartResult { part, answer, timings };
+        }
+    }
+}
+
+// Run the selected parts of each day, repeating each as requested.  days holds each
+// day's number and input text, or None if there's no input to run it on.
+//
+// Parts are run on up to options.jobs threads at once.  report is called with each
+// day's index in days and its results, in order, as soon as all of that day's parts
+// are done.  (A day without input has no results.)  If report fails, no more parts
+// are started and its error is returned.
+pub fn run_days<F, E>(days: &[(usize, Option<&str>)], options: &Options, mut report: F) -> Result<(), E>
+    where F: FnMut(usize, Vec<PartResult>) -> Result<(), E>
+{
+    let parts: Vec<usize> = [1, 2].into_iter().filter(|p| options.runs_part(*p)).collect();
+
+    // Every part to run, as (index into days, part)
+    let mut tasks = Vec::new();
+    for (n, (_, text)) in days.iter().enumerate() {
+        if text.is_some() {
+            for part in &parts {
+                tasks.push((n, *part));
+            }
+        }
+    }
+
+    // Parts still to finish for each day.
+    let mut remaining: Vec<usize> = days.iter()
+        .map(|(_, text)| if text.is_some() { parts.len() } else { 0 })
+        .collect();
+    let mut finished: Vec<Vec<PartResult>> = days.iter().map(|_| Vec::new()).collect();
+
+    let next_task = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(tasks.len()) {
+            let sender = sender.clone();
+            let (tasks, next_task, stop) = (&tasks, &next_task, &stop);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(&(n, part)) = tasks.get(next_task.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    let (day_no, text) = days[n];
+                    let result = run_repeated(day_no, part, text.unwrap(), &options.params, options.repeat);
+                    if sender.send((n, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // Report days in order, as they complete.
+        let mut next_day = 0;
+        while next_day < days.len() {
+            if remaining[next_day] == 0 {
+                let mut results = std::mem::take(&mut finished[next_day]);
+                results.sort_by_key(|r| r.part);
+                if let Err(e) = report(next_day, results) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                next_day += 1;
+            }
+            else {
+                // Every task is sent by a worker before it finishes.
+                let (n, result) = receiver.recv().expect("a worker thread died");
+                finished[n].push(result);
+                remaining[n] -= 1;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::Options;
+    use crate::day::Answer;
+    use crate::runner::run_days;
+
+    #[test]
+    fn test_run_days() {
+        let options = Options::parse(["--jobs", "3"].iter().map(|s| s.to_string())).unwrap();
+        let example = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let days = [(1, Some(example)), (3, None), (24, Some("")), (1, Some("3 x\n"))];
+
+        // Results come back in the order of days, with parts in order.
+        let mut reported = Vec::new();
+        let result: Result<(), ()> = run_days(&days, &options, |n, results| {
+            let answers: Vec<(usize, bool)> = results.iter().map(|r| (r.part, r.answer.is_ok())).collect();
+            reported.push((n, answers));
+            if n == 0 {
+                assert_eq!(results[0].answer, Ok(Answer::Numeric(11)));
+                assert_eq!(results[1].answer, Ok(Answer::Numeric(31)));
+            }
+            Ok(())
+        });
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(reported, vec![
+            (0, vec![(1, true), (2, true)]),
+            (1, vec![]),
+            (2, vec![(1, true), (2, true)]),
+            (3, vec![(1, false), (2, false)]),
+        ]);
+    }
+}
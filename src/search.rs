@@ -0,0 +1,361 @@
+use std::{cmp::Ordering, collections::{BinaryHeap, HashMap, HashSet, VecDeque}, hash::Hash, ops::Add};
+
+// Searches over graphs given implicitly by a function from a state to its
+// neighbours.  States can be anything hashable: a grid position, a position and
+// heading, and so on.
+//
+// bfs() and bfs_path() count steps.  The rest take the cost of each step along
+// with the neighbour, as (state, cost) pairs, and need costs to be positive.
+
+// The costs a search can add up and compare.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+// The number of steps from start to every state that can be reached.
+pub fn bfs<S, F, I>(start: S, mut neighbors: F) -> HashMap<S, usize>
+    where S: Clone + Eq + Hash,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = S>
+{
+    let mut dist = HashMap::new();
+    let mut frontier = VecDeque::new();
+    dist.insert(start.clone(), 0);
+    frontier.push_back(start);
+
+    while let Some(state) = frontier.pop_front() {
+        let d = dist[&state];
+        for next in neighbors(&state) {
+            if !dist.contains_key(&next) {
+                dist.insert(next.clone(), d+1);
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    dist
+}
+
+// A shortest path from start to a goal, counting steps, including both ends.
+pub fn bfs_path<S, F, I, G>(start: S, mut neighbors: F, mut is_goal: G) -> Option<Vec<S>>
+    where S: Clone + Eq + Hash,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = S>,
+          G: FnMut(&S) -> bool
+{
+    let mut preds: HashMap<S, Option<S>> = HashMap::new();
+    let mut frontier = VecDeque::new();
+    preds.insert(start.clone(), None);
+    frontier.push_back(start);
+
+    while let Some(state) = frontier.pop_front() {
+        if is_goal(&state) {
+            return Some(walk_back(state, |s| preds[s].clone()));
+        }
+        for next in neighbors(&state) {
+            if !preds.contains_key(&next) {
+                preds.insert(next.clone(), Some(state.clone()));
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+// The cheapest cost from start to every state that can be reached.
+pub fn dijkstra<S, C, F, I>(start: S, mut successors: F) -> HashMap<S, C>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>
+{
+    let mut dist = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    dist.insert(start.clone(), C::default());
+    queue.push(Entry { priority: C::default(), cost: C::default(), state: start });
+
+    while let Some(Entry { cost, state, .. }) = queue.pop() {
+        if cost > dist[&state] {
+            // A cheaper way here has already been explored.
+            continue;
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if dist.get(&next).is_none_or(|d| next_cost < *d) {
+                dist.insert(next.clone(), next_cost);
+                queue.push(Entry { priority: next_cost, cost: next_cost, state: next });
+            }
+        }
+    }
+
+    dist
+}
+
+// A cheapest path from start to a goal, including both ends, and its cost.
+pub fn dijkstra_path<S, C, F, I, G>(start: S, successors: F, is_goal: G) -> Option<(Vec<S>, C)>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          G: FnMut(&S) -> bool
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+// A cheapest path from start to a goal, guided by a heuristic that estimates the
+// cost from a state to the nearest goal.  The estimate must never be too high, or
+// the path found may not be the cheapest.
+pub fn astar<S, C, F, I, H, G>(start: S, mut successors: F, mut heuristic: H, mut is_goal: G) -> Option<(Vec<S>, C)>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          H: FnMut(&S) -> C,
+          G: FnMut(&S) -> bool
+{
+    let mut dist: HashMap<S, C> = HashMap::new();
+    let mut preds: HashMap<S, S> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    dist.insert(start.clone(), C::default());
+    queue.push(Entry { priority: heuristic(&start), cost: C::default(), state: start });
+
+    while let Some(Entry { cost, state, .. }) = queue.pop() {
+        if cost > dist[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some((walk_back(state, |s| preds.get(s).cloned()), cost));
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if dist.get(&next).is_none_or(|d| next_cost < *d) {
+                dist.insert(next.clone(), next_cost);
+                preds.insert(next.clone(), state.clone());
+                let priority = next_cost + heuristic(&next);
+                queue.push(Entry { priority, cost: next_cost, state: next });
+            }
+        }
+    }
+
+    None
+}
+
+// Every cheapest path from a start to the goals, as found by dijkstra_all().
+pub struct AllPaths<S, C> {
+    pub cost: C,                   // The cost of the cheapest paths.
+    pub goals: Vec<S>,             // The goals that can be reached at that cost.
+    pub preds: HashMap<S, Vec<S>>, // The states just before each state on its cheapest paths.
+    order: Vec<S>,                 // The states explored, cheapest first.
+}
+
+impl<S: Clone + Eq + Hash, C> AllPaths<S, C> {
+    // Every state on a cheapest path to one of the goals.
+    pub fn states(&self) -> HashSet<S> {
+        let mut states = HashSet::new();
+        let mut to_visit = self.goals.clone();
+
+        while let Some(state) = to_visit.pop() {
+            if states.insert(state.clone()) && let Some(preds) = self.preds.get(&state) {
+                to_visit.extend(preds.iter().cloned());
+            }
+        }
+
+        states
+    }
+
+    // The number of different cheapest paths, to all the goals.
+    pub fn count(&self) -> usize {
+        // The cheapest paths into a state come from states explored before it.
+        let mut paths: HashMap<&S, usize> = HashMap::new();
+        for state in &self.order {
+            let n = match self.preds.get(state) {
+                Some(preds) => preds.iter().map(|p| paths[p]).sum(),
+                None => 1,  // The start.
+            };
+            paths.insert(state, n);
+        }
+
+        self.goals.iter().map(|goal| paths[goal]).sum()
+    }
+}
+
+// All the cheapest paths from start to the goals: the paths to the cheapest goal,
+// and to any others that cost the same.  Goals end paths; the search doesn't go
+// through them.
+pub fn dijkstra_all<S, C, F, I, G>(start: S, mut successors: F, mut is_goal: G) -> Option<AllPaths<S, C>>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          G: FnMut(&S) -> bool
+{
+    let mut dist: HashMap<S, C> = HashMap::new();
+    let mut preds: HashMap<S, Vec<S>> = HashMap::new();
+    let mut explored: HashSet<S> = HashSet::new();
+    let mut order = Vec::new();
+    let mut goals = Vec::new();
+    let mut best = None;
+    let mut queue = BinaryHeap::new();
+    dist.insert(start.clone(), C::default());
+    queue.push(Entry { priority: C::default(), cost: C::default(), state: start });
+
+    while let Some(Entry { cost, state, .. }) = queue.pop() {
+        if best.is_some_and(|best| cost > best) {
+            // Everything left costs more than the goals found.
+            break;
+        }
+        if cost > dist[&state] || !explored.insert(state.clone()) {
+            continue;
+        }
+        order.push(state.clone());
+
+        if is_goal(&state) {
+            best = Some(cost);
+            goals.push(state);
+            continue;
+        }
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            match dist.get(&next) {
+                Some(d) if next_cost > *d => (),
+                Some(d) if next_cost == *d => {
+                    // Another way to get there at the same cost.
+                    preds.entry(next).or_default().push(state.clone());
+                }
+                _ => {
+                    dist.insert(next.clone(), next_cost);
+                    preds.insert(next.clone(), vec![state.clone()]);
+                    queue.push(Entry { priority: next_cost, cost: next_cost, state: next });
+                }
+            }
+        }
+    }
+
+    best.map(|cost| AllPaths { cost, goals, preds, order })
+}
+
+// Follow predecessors back from a state, giving the path from the start to it.
+fn walk_back<S, P>(end: S, mut pred: P) -> Vec<S>
+    where P: FnMut(&S) -> Option<S>
+{
+    let mut path = vec![end];
+    while let Some(prev) = pred(path.last().unwrap()) {
+        path.push(prev);
+    }
+    path.reverse();
+
+    path
+}
+
+// A state waiting in a search's queue.  The queue is a max-heap, so entries order
+// in reverse, with the lowest priority first.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::{Grid, Pos};
+    use crate::search::{astar, bfs, bfs_path, dijkstra, dijkstra_all, dijkstra_path};
+
+    const MAZE: &str = "\
+.....
+.###.
+...#.
+.#...
+";
+
+    fn open(grid: &Grid<bool>) -> impl FnMut(&Pos) -> Vec<Pos> + '_ {
+        |pos| grid.neighbors4(*pos).filter(|n| grid[*n]).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse_chars(0, MAZE).unwrap().map(|c| *c == '.');
+
+        let dist = bfs((0, 0), open(&grid));
+        assert_eq!(dist[&(0, 0)], 0);
+        assert_eq!(dist[&(4, 3)], 7);
+        assert_eq!(dist.get(&(1, 1)), None);
+        assert_eq!(dist.len(), 15);
+
+        let path = bfs_path((0, 0), open(&grid), |pos| *pos == (4, 3)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!((path[0], path[7]), ((0, 0), (4, 3)));
+        assert_eq!(bfs_path((0, 0), open(&grid), |pos| *pos == (1, 1)), None);
+    }
+
+    // A small weighted graph: 0 -> 1 -> 3 and 0 -> 2 -> 3 both cost 5, 0 -> 3 costs 6.
+    fn edges(n: &usize) -> Vec<(usize, usize)> {
+        match n {
+            0 => vec![(1, 1), (2, 3), (3, 6)],
+            1 => vec![(3, 4)],
+            2 => vec![(3, 2)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let dist = dijkstra(0, edges);
+        assert_eq!(dist[&3], 5);
+        assert_eq!(dist[&4], 6);
+
+        assert_eq!(dijkstra_path(0, edges, |n| *n == 4), Some((vec![0, 1, 3, 4], 6)));
+        assert_eq!(dijkstra_path(1, edges, |n| *n == 0), None);
+
+        // With the Manhattan distance to the corner as the heuristic.
+        let grid = Grid::parse_chars(0, MAZE).unwrap().map(|c| *c == '.');
+        let steps = |pos: &Pos| grid.neighbors4(*pos).filter(|n| grid[*n]).map(|n| (n, 1)).collect::<Vec<_>>();
+        let (path, cost) = astar((0, 0), steps, |pos| (4 - pos.0) + (3 - pos.1), |pos| *pos == (4, 3)).unwrap();
+        assert_eq!((path.len(), cost), (8, 7));
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        let all = dijkstra_all(0, edges, |n| *n == 3).unwrap();
+        assert_eq!(all.cost, 5);
+        assert_eq!(all.goals, vec![3]);
+        assert_eq!(all.count(), 2);
+        assert_eq!(all.states().len(), 4);
+
+        // The search stops at the cheapest goal.
+        let all = dijkstra_all(0, edges, |n| *n == 1 || *n == 4).unwrap();
+        assert_eq!((all.cost, all.goals.clone()), (1, vec![1]));
+
+        // Two goals at the same cost.
+        let all = dijkstra_all(0, |n: &usize| if *n == 0 { vec![(1, 1), (2, 1)] } else { vec![] }, |n| *n > 0).unwrap();
+        assert_eq!((all.cost, all.goals.len(), all.count()), (1, 2, 2));
+
+        // Steps of 1 or 2, each costing their length: there are 5 ways to get to 4.
+        let all = dijkstra_all(0, |n: &usize| vec![(n+1, 1), (n+2, 2)], |n| *n == 4).unwrap();
+        assert_eq!((all.cost, all.count()), (4, 5));
+
+        assert!(dijkstra_all(4, edges, |n| *n == 0).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{cell::Cell, time::{Duration, Instant}};
+
+// Each part is timed as a whole by the runner.  The time spent reading the input
+// is measured by the days themselves, by wrapping Input::read() in timing::parse(),
+// so the runner can split a part's time into parse and solve time.
+
+thread_local! {
+    static PARSE_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+// Call f, counting the time it takes as parse time.
+pub fn parse<T, F>(f: F) -> T
+    where F: FnOnce() -> T
+{
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    PARSE_TIME.with(|t| t.set(t.get() + elapsed));
+
+    result
+}
+
+// Parse time accumulated on this thread since the last call.
+fn take_parse_time() -> Duration {
+    PARSE_TIME.with(|t| t.replace(Duration::ZERO))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+// Call f, timing it.
+pub fn time<T, F>(f: F) -> (T, Timing)
+    where F: FnOnce() -> T
+{
+    take_parse_time();
+    let start = Instant::now();
+    let result = f();
+    let total = start.elapsed();
+    let parse = take_parse_time().min(total);
+
+    (result, Timing { parse, solve: total - parse })
+}
+
+// Summary of repeated runs of a part.  Min, median, mean and stddev are of the total
+// (parse + solve) time.  Parse and solve are means.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Stats {
+    pub runs: usize,
+    pub parse: Duration,
+    pub solve: Duration,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(timings: &[Timing]) -> Stats {
+        let runs = timings.len();
+        if runs == 0 {
+            return Stats::default();
+        }
+
+        let mut totals: Vec<Duration> = timings.iter().map(|t| t.total()).collect();
+        totals.sort();
+
+        let median = if runs % 2 == 1 {
+            totals[runs/2]
+        }
+        else {
+            (totals[runs/2 - 1] + totals[runs/2]) / 2
+        };
+
+        let n = runs as u32;
+        let parse = timings.iter().map(|t| t.parse).sum::<Duration>() / n;
+        let solve = timings.iter().map(|t| t.solve).sum::<Duration>() / n;
+        let mean = totals.iter().sum::<Duration>() / n;
+
+        let variance = totals.iter()
+            .map(|t| (t.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>() / runs as f64;
+
+        Stats {
+            runs,
+            parse,
+            solve,
+            min: totals[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    // Combine the stats of several parts, as if they were run one after another.
+    pub fn total(all: &[Stats]) -> Stats {
+        let variance: f64 = all.iter().map(|s| s.stddev.as_secs_f64().powi(2)).sum();
+
+        Stats {
+            runs: all.iter().map(|s| s.runs).min().unwrap_or(0),
+            parse: all.iter().map(|s| s.parse).sum(),
+            solve: all.iter().map(|s| s.solve).sum(),
+            min: all.iter().map(|s| s.min).sum(),
+            median: all.iter().map(|s| s.median).sum(),
+            mean: all.iter().map(|s| s.mean).sum(),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+// Format a duration with units suited to its size, e.g. "850 ns", "12.3 µs" or "4.56 ms"
+pub fn format_duration(d: Duration) -> String {
+    let ns = d.as_nanos();
+    if ns < 1_000 {
+        format!("{ns} ns")
+    }
+    else if ns < 1_000_000 {
+        format!("{:.1} µs", ns as f64 / 1e3)
+    }
+    else if ns < 1_000_000_000 {
+        format!("{:.2} ms", ns as f64 / 1e6)
+    }
+    else {
+        format!("{:.3} s", d.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::timing::{self, Stats, Timing, format_duration};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let (answer, t) = timing::time(|| {
+            let n = timing::parse(|| { std::thread::sleep(ms(20)); 5 });
+            std::thread::sleep(ms(10));
+            n * 2
+        });
+
+        assert_eq!(answer, 10);
+        assert!(t.parse >= ms(20));
+        assert!(t.solve >= ms(10));
+
+        // Nothing is left over for the next part.
+        let (_, t) = timing::time(|| ());
+        assert!(t.parse < ms(1));
+    }
+
+    #[test]
+    fn test_stats() {
+        let timings: Vec<Timing> = [4, 1, 3, 2].iter()
+            .map(|n| Timing { parse: ms(1), solve: ms(*n) })
+            .collect();
+        let stats = Stats::new(&timings);
+
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.parse, ms(1));
+        assert_eq!(stats.solve, Duration::from_micros(2500));
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, Duration::from_micros(3500));
+        assert_eq!(stats.mean, Duration::from_micros(3500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+
+        let total = Stats::total(&[stats, stats]);
+        assert_eq!(total.mean, ms(7));
+        assert_eq!(total.min, ms(4));
+        assert_eq!(total.stddev.as_micros(), 1581);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(850)), "850 ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3 µs");
+        assert_eq!(format_duration(Duration::from_micros(4_560)), "4.56 ms");
+        assert_eq!(format_duration(Duration::from_millis(1_234)), "1.234 s");
+    }
+}
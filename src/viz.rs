@@ -0,0 +1,368 @@
+use std::{fs, io::{self, Write}, path::{Path, PathBuf}};
+
+pub use rgb::RGB8;
+
+use crate::cli::Options;
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::inputs::InputProvider;
+use crate::params::Params;
+use crate::registry::{self, YEAR};
+
+// Pictures of a day's puzzle.  A day that can draw itself implements Visualize,
+// giving a series of frames, each a grid of coloured characters.  A frame can be
+// printed to a terminal with ANSI colour codes, or saved as a PPM or PNG image
+// with a block of pixels per cell.  The runner's --viz option shows them.
+
+// Colours the days share, so their pictures look alike.
+pub const BACKGROUND: RGB8 = RGB8::new(60, 60, 70);
+pub const WALL: RGB8 = RGB8::new(140, 140, 150);
+pub const PATH: RGB8 = RGB8::new(240, 200, 60);
+pub const HIGHLIGHT: RGB8 = RGB8::new(230, 70, 60);
+pub const START: RGB8 = RGB8::new(80, 200, 90);
+
+// The size of a cell in images, in pixels.
+pub const CELL_PIXELS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: RGB8,
+}
+
+impl Cell {
+    pub const fn new(ch: char, color: RGB8) -> Cell {
+        Cell { ch, color }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub cells: Grid<Cell>,
+}
+
+impl Frame {
+    pub fn new(caption: &str, cells: Grid<Cell>) -> Frame {
+        Frame { caption: caption.to_string(), cells }
+    }
+
+    // A frame drawing each cell of grid with f.
+    pub fn from_grid<T, F>(caption: &str, grid: &Grid<T>, f: F) -> Frame
+        where F: FnMut(&T) -> Cell
+    {
+        Frame::new(caption, grid.map(f))
+    }
+
+    // The frame's characters, without colour.
+    pub fn text(&self) -> String {
+        self.cells.render(|cell| cell.ch)
+    }
+
+    // The frame's characters, coloured with 24-bit ANSI escape codes.
+    pub fn ansi(&self) -> String {
+        let mut out = String::new();
+        for row in self.cells.rows() {
+            let mut color = None;
+            for cell in row {
+                if color != Some(cell.color) {
+                    let RGB8 { r, g, b } = cell.color;
+                    out.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+                    color = Some(cell.color);
+                }
+                out.push(cell.ch);
+            }
+            out.push_str("\x1b[0m\n");
+        }
+
+        out
+    }
+
+    // The pixels of an image of the frame, row by row.
+    fn pixels(&self, scale: usize) -> (usize, usize, Vec<RGB8>) {
+        let (width, height) = (self.cells.width()*scale, self.cells.height()*scale);
+        let mut pixels = Vec::with_capacity(width*height);
+        for row in self.cells.rows() {
+            let line: Vec<RGB8> = row.iter()
+                .flat_map(|cell| std::iter::repeat_n(cell.color, scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        (width, height, pixels)
+    }
+
+    // The frame as a binary PPM image, scale pixels to a cell.
+    pub fn ppm(&self, scale: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.pixels(scale);
+        let mut out = format!("P6\n{width} {height}\n255\n").into_bytes();
+        for p in pixels {
+            out.extend_from_slice(&[p.r, p.g, p.b]);
+        }
+
+        out
+    }
+
+    // The frame as a PNG image, scale pixels to a cell.  The image data is
+    // stored without compression, which keeps the encoder small.
+    pub fn png(&self, scale: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.pixels(scale);
+
+        // Each row starts with its filter type, 0 for none.
+        let mut raw = Vec::with_capacity((width*3 + 1)*height);
+        for row in pixels.chunks(width.max(1)) {
+            raw.push(0);
+            for p in row {
+                raw.extend_from_slice(&[p.r, p.g, p.b]);
+            }
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(width as u32).to_be_bytes());
+        header.extend_from_slice(&(height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);  // 8-bit RGB, no interlacing.
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut out, b"IHDR", &header);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+
+        out
+    }
+}
+
+// A day that can draw how it solves its puzzle.
+pub trait Visualize {
+    // The frames showing the puzzle, in order.
+    fn frames(&self, input: &str, params: &Params) -> Result<Vec<Frame>, SolveError>;
+}
+
+// How --viz shows the frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ansi,
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Format> {
+        match s {
+            "ansi" => Some(Format::Ansi),
+            "ppm" => Some(Format::Ppm),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Ansi => "txt",
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+}
+
+// The directory images are written to, unless --viz-dir says otherwise.
+pub const DEFAULT_DIR: &str = "viz";
+
+// A colour for the nth of many things, like a map's regions.  Successive colours
+// are spread around the colour wheel by the golden angle, so neighbours differ.
+pub fn palette(n: usize) -> RGB8 {
+    let hue = (n as f64 * 137.508) % 360.0;
+    let (s, v) = (0.65, 0.9);
+
+    // HSV to RGB
+    let c = v * s;
+    let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as usize {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let channel = |value: f64| ((value + v - c) * 255.0).round() as u8;
+
+    RGB8::new(channel(r), channel(g), channel(b))
+}
+
+// Save frames as images in dir, named like day06_001.png.  Returns their paths.
+pub fn save(day: usize, frames: &[Frame], format: Format, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths = Vec::new();
+    for (n, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("day{day:02}_{:03}.{}", n+1, format.extension()));
+        let data = match format {
+            Format::Ansi => frame.ansi().into_bytes(),
+            Format::Ppm => frame.ppm(CELL_PIXELS),
+            Format::Png => frame.png(CELL_PIXELS),
+        };
+        fs::write(&path, data)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+// Show the frames of each day selected by options: printed in colour for ANSI,
+// otherwise saved as images.
+pub fn run(options: &Options, format: Format) -> io::Result<()> {
+    let inputs = match &options.input {
+        Some(path) => InputProvider::from_path(path),
+        None => InputProvider::new(options.input_dir.as_deref()),
+    };
+    let dir = Path::new(options.viz_dir.as_deref().unwrap_or(DEFAULT_DIR));
+
+    let mut out = io::stdout().lock();
+    for &day in &options.days {
+        let Some(solver) = registry::find(YEAR, day) else {
+            continue;
+        };
+        let Some(viz) = solver.day.visualizer() else {
+            writeln!(out, "Day {day}: no visualization")?;
+            continue;
+        };
+        let text = match inputs.load(day) {
+            Ok(text) => text,
+            Err(e) => {
+                writeln!(out, "Day {day}: {e}")?;
+                continue;
+            }
+        };
+
+        let frames = Params::new(solver.day.params(), &options.params)
+            .map_err(|e| SolveError::bad_input(day, &e))
+            .and_then(|params| viz.frames(&text, &params));
+        let frames = match frames {
+            Ok(frames) => frames,
+            Err(e) => {
+                writeln!(out, "Day {day}: {e}")?;
+                continue;
+            }
+        };
+
+        match format {
+            Format::Ansi => {
+                for frame in &frames {
+                    writeln!(out, "Day {day}: {}\n{}", frame.caption, frame.ansi())?;
+                }
+            }
+            _ => {
+                let paths = save(day, &frames, format, dir)?;
+                let plural = if paths.len() == 1 { "" } else { "s" };
+                writeln!(out, "Day {day}: wrote {} frame{plural} to {}", paths.len(), dir.display())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Wrap data in a zlib stream of uncompressed ("stored") deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::Grid;
+    use crate::viz::{Cell, Format, Frame, RGB8, adler32, crc32, palette};
+
+    fn frame() -> Frame {
+        let grid = Grid::parse_chars(0, "#.\n.#\n").unwrap();
+        Frame::from_grid("test", &grid, |c| match c {
+            '#' => Cell::new('#', RGB8::new(255, 0, 0)),
+            _ => Cell::new('.', RGB8::new(0, 0, 255)),
+        })
+    }
+
+    #[test]
+    fn test_text() {
+        let frame = frame();
+
+        assert_eq!(frame.text(), "#.\n.#\n");
+        assert_eq!(frame.ansi(), "\x1b[38;2;255;0;0m#\x1b[38;2;0;0;255m.\x1b[0m\n\x1b[38;2;0;0;255m.\x1b[38;2;255;0;0m#\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_images() {
+        let frame = frame();
+
+        let ppm = frame.ppm(2);
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4*4*3);
+        assert_eq!(&ppm[11..17], &[255, 0, 0, 255, 0, 0]);
+
+        let png = frame.png(2);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x04\0\0\0\x04"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+
+        // Known checksums.
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_palette() {
+        let colors: Vec<RGB8> = (0..10).map(palette).collect();
+        for (i, a) in colors.iter().enumerate() {
+            assert!(colors[i+1..].iter().all(|b| a != b));
+        }
+
+        assert_eq!(Format::parse("png"), Some(Format::Png));
+        assert_eq!(Format::parse("gif"), None);
+        assert_eq!(Format::Ppm.extension(), "ppm");
+    }
+}
@@ -0,0 +1,232 @@
+use std::{env, ffi::OsString, fs, io::{self, Write}, path::{Path, PathBuf}, process::Command, thread, time::{Duration, SystemTime}};
+
+use crate::cli::Options;
+use crate::day::Answer;
+use crate::inputs::InputProvider;
+use crate::runner::{PartResult, run_repeated};
+use crate::timing::format_duration;
+
+// Watch mode: run a day, then keep polling its input file and run it again
+// whenever the input changes, showing how the answers and times differ from the
+// last run.  The day's source, src/dayN.rs, is watched too: when it changes the
+// binary is rebuilt with cargo and restarted.  Polling uses only the standard
+// library, checking each file's modification time and size.
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// A file being watched.
+pub struct Watched {
+    pub path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,  // Modification time and size, or None if it can't be read.
+}
+
+impl Watched {
+    pub fn new(path: PathBuf) -> Watched {
+        let stamp = stamp(&path);
+        Watched { path, stamp }
+    }
+
+    // Has the file changed (or appeared, or gone) since the last check?
+    pub fn changed(&mut self) -> bool {
+        let stamp = stamp(&self.path);
+        let changed = stamp != self.stamp;
+        self.stamp = stamp;
+
+        changed
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+// Describe one part's result, compared with the previous run's if there was one, e.g.
+//   Part 1: 22 (was 20)  parse 1.20 ms  solve 2.00 ms  (-5.0%)
+pub fn describe(result: &PartResult, prev: Option<&PartResult>) -> String {
+    let stats = result.stats();
+    let mut line = format!("Part {}: ", result.part);
+
+    match &result.answer {
+        Ok(Answer::None) => line.push('-'),
+        Ok(answer) => line.push_str(&answer.to_string()),
+        Err(e) => line.push_str(&e.to_string()),
+    }
+
+    if let Some(prev) = prev {
+        match (&prev.answer, &result.answer) {
+            (Ok(a), Ok(b)) if a == b => line.push_str(" (unchanged)"),
+            (Ok(Answer::None), _) => line.push_str(" (was -)"),
+            (Ok(a), _) => line.push_str(&format!(" (was {a})")),
+            (Err(_), Ok(_)) => line.push_str(" (was an error)"),
+            (Err(_), Err(_)) => (),
+        }
+    }
+
+    if result.answer.is_ok() {
+        line.push_str(&format!("  parse {}  solve {}", format_duration(stats.parse), format_duration(stats.solve)));
+        if let Some(prev) = prev && prev.answer.is_ok() {
+            let was = prev.stats().mean.as_secs_f64();
+            if was > 0.0 {
+                let change = 100.0 * (stats.mean.as_secs_f64() - was) / was;
+                line.push_str(&format!("  ({change:+.1}%)"));
+            }
+        }
+    }
+
+    line
+}
+
+// Watch the day selected by options, until interrupted.
+pub fn watch(options: &Options) -> io::Result<()> {
+    let day = options.days[0];
+    let inputs = match &options.input {
+        Some(path) => InputProvider::from_path(path),
+        None => InputProvider::new(options.input_dir.as_deref()),
+    };
+    let parts: Vec<usize> = [1, 2].into_iter().filter(|p| options.runs_part(*p)).collect();
+
+    let Some(input_path) = inputs.day_path(day) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "can't watch stdin"));
+    };
+    let mut input = Watched::new(input_path);
+    let source_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join(format!("day{day}.rs"));
+    let mut source = source_path.exists().then(|| Watched::new(source_path));
+
+    let mut out = io::stdout();
+    match &source {
+        Some(source) => writeln!(out, "Watching {} and {} (Ctrl-C to stop)", input.path.display(), source.path.display())?,
+        None => writeln!(out, "Watching {} (Ctrl-C to stop)", input.path.display())?,
+    }
+
+    let mut prev: Option<Vec<PartResult>> = None;
+    let mut run = true;
+    loop {
+        if run {
+            writeln!(out, "\nDay {day}:")?;
+            match inputs.load(day) {
+                Ok(text) => {
+                    let results: Vec<PartResult> = parts.iter()
+                        .map(|part| run_repeated(day, *part, &text, &options.params, options.repeat))
+                        .collect();
+                    for result in &results {
+                        let last = prev.as_ref().and_then(|p| p.iter().find(|r| r.part == result.part));
+                        writeln!(out, "  {}", describe(result, last))?;
+                    }
+                    prev = Some(results);
+                }
+                Err(e) => writeln!(out, "  {e}")?,
+            }
+            out.flush()?;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+
+        if let Some(source) = &mut source && source.changed() {
+            writeln!(out, "\n{} changed, rebuilding", source.path.display())?;
+            if rebuild()? {
+                return Err(restart());
+            }
+            writeln!(out, "Build failed, still running the old code")?;
+        }
+
+        run = input.changed();
+        if run {
+            write!(out, "\n{} changed", input.path.display())?;
+        }
+    }
+}
+
+// Build the binary again, the same way this one was built.  Returns whether it worked.
+fn rebuild() -> io::Result<bool> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut command = Command::new(cargo);
+    command.current_dir(env!("CARGO_MANIFEST_DIR")).args(["build", "--bin", "aoc2024"]);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    if cfg!(feature = "embedded") {
+        command.args(["--features", "embedded"]);
+    }
+
+    Ok(command.status()?.success())
+}
+
+// Replace this process with the newly built binary, given the same arguments.  The
+// path it was started by is used, since the running file has just been replaced.
+// Only returns if that fails.
+fn restart() -> io::Error {
+    let mut args = env::args_os();
+    let program = match (args.next(), env::current_exe()) {
+        (Some(program), _) => PathBuf::from(program),
+        (None, Ok(exe)) => exe,
+        (None, Err(e)) => { return e; }
+    };
+    let mut command = Command::new(program);
+    command.args(args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.exec()
+    }
+
+    #[cfg(not(unix))]
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => e,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, time::Duration};
+
+    use crate::day::Answer;
+    use crate::error::SolveError;
+    use crate::runner::PartResult;
+    use crate::timing::Timing;
+    use crate::watch::{Watched, describe};
+
+    fn result(answer: Result<Answer, SolveError>, solve_us: u64) -> PartResult {
+        let timing = Timing { parse: Duration::from_micros(100), solve: Duration::from_micros(solve_us) };
+        PartResult { part: 1, answer, timings: vec![timing] }
+    }
+
+    #[test]
+    fn test_describe() {
+        let first = result(Ok(Answer::Numeric(20)), 900);
+        assert_eq!(describe(&first, None), "Part 1: 20  parse 100.0 µs  solve 900.0 µs");
+
+        let same = result(Ok(Answer::Numeric(20)), 1900);
+        assert_eq!(describe(&same, Some(&first)), "Part 1: 20 (unchanged)  parse 100.0 µs  solve 1.90 ms  (+100.0%)");
+
+        let changed = result(Ok(Answer::Numeric(22)), 900);
+        assert_eq!(describe(&changed, Some(&same)), "Part 1: 22 (was 20)  parse 100.0 µs  solve 900.0 µs  (-50.0%)");
+
+        let failed = result(Err(SolveError::bad_input(18, "too few bytes")), 0);
+        assert_eq!(describe(&failed, Some(&changed)), "Part 1: day 18 bad input: too few bytes (was 22)");
+        assert_eq!(describe(&changed, Some(&failed)), "Part 1: 22 (was an error)  parse 100.0 µs  solve 900.0 µs");
+    }
+
+    #[test]
+    fn test_watched() {
+        let path = env::temp_dir().join(format!("aoc2024_watch_{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut watched = Watched::new(path.clone());
+        assert!(!watched.changed());
+
+        fs::write(&path, "1\n").unwrap();
+        assert!(watched.changed());
+        assert!(!watched.changed());
+
+        // A change in size is seen even if the modification time doesn't move on.
+        fs::write(&path, "1\n2\n").unwrap();
+        assert!(watched.changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watched.changed());
+    }
+}
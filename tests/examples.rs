@@ -0,0 +1,18 @@
+// The puzzles' worked examples, one test each.  build.rs generates a test for
+// every examples/dayN_name.txt, which calls check_example() with its name.
+
+use std::path::Path;
+
+use aoc2024::examples::{EXAMPLES_DIR, Example};
+
+fn check_example(name: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(EXAMPLES_DIR).join(format!("{name}.txt"));
+    let example = Example::load(&path).unwrap();
+
+    assert!(example.answers.iter().any(|a| a.is_some()), "{name} has no answers to check");
+    if let Err(wrong) = example.check() {
+        panic!("{wrong}");
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));